@@ -1,7 +1,9 @@
 use std::env;
 
+use rand::prelude::*;
+
 use osmff_lib::firefighter::problem::{OSMFProblem, OSMFSettings};
-use osmff_lib::firefighter::strategy::OSMFStrategy;
+use osmff_lib::firefighter::strategy::{EXACT_NODE_LIMIT, OSMFStrategy};
 
 #[derive(Debug)]
 struct BenchResults {
@@ -9,6 +11,20 @@ struct BenchResults {
     avg_def: f64,
     avg_end_time: f64,
     avg_sim_millis: f64,
+    optimality: Option<OptimalityResults>,
+}
+
+/// Comparison of a strategy with the exact strategy on the same fire roots
+#[derive(Debug)]
+struct OptimalityResults {
+    /// Average number of nodes that burned in addition to those burning under the best defense
+    /// sequence found by the exact strategy. The gap is negative if the strategy found a better
+    /// defense sequence than the exact strategy within its search limit.
+    avg_gap: f64,
+    /// Average gap relative to the number of nodes burning under the best defense sequence
+    avg_relative_gap: f64,
+    /// Number of runs for which the exact strategy proved its defense sequence to be optimal
+    num_proven_optimal: usize,
 }
 
 fn main() {
@@ -22,6 +38,18 @@ fn main() {
 
     let args: Vec<_> = env::args().collect();
 
+    // Print all strategies that can be benchmarked with their tunable parameters and default
+    // values. Custom strategies are not registered by this binary and cannot be benchmarked.
+    if args.contains(&"--list".to_string()) {
+        for strategy in OSMFStrategy::available_strategies_with_parameters() {
+            let params: Vec<_> = strategy.parameters.iter()
+                .map(|param| format!("{}={}", param.name, param.default))
+                .collect();
+            println!("{}", [vec![strategy.name], params].concat().join(" "));
+        }
+        return;
+    }
+
     if !args.contains(&"--graph".to_string()) {
         let err = "Missing required argument: --graph";
         log::error!("{}", err);
@@ -38,12 +66,19 @@ fn main() {
         num_roots: 1,
         num_ffs: 1,
         strategy_every: 1,
+        ..OSMFSettings::default()
     };
 
     let mut loop_count: usize = 1;
+    let mut compare_optimal = false;
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
+            "--optimal" => {
+                compare_optimal = true;
+                i += 1;
+                continue;
+            }
             "--graph" => {
                 settings.graph_name = args[i+1].clone();
             }
@@ -62,10 +97,19 @@ fn main() {
                 settings.strategy_every = args[i+1].parse()
                     .expect("Invalid argument: strategy_every");
             }
+            "-s" => {
+                settings.seed = Some(args[i+1].parse()
+                    .expect("Invalid argument: seed"));
+            }
             "--loop" => {
                 loop_count = args[i+1].parse()
                     .expect("Invalid argument: loop_count");
             }
+            "-p" => {
+                let (name, value) = args[i+1].split_once('=')
+                    .expect("Invalid argument: strategy parameter must be name=value");
+                settings.strategy_params.insert(name.to_string(), value.parse().unwrap());
+            }
             _ => {
                 let err = format!("Unknown argument: {}", &args[i]);
                 log::error!("{}", &err);
@@ -75,11 +119,22 @@ fn main() {
         i += 2;
     }
 
+    // Both strategies have to face the same fire roots
+    if compare_optimal && settings.seed.is_none() {
+        settings.seed = Some(thread_rng().gen());
+    }
+
     log::info!("Benchmarking with the following problem settings: {:?}", &settings);
     log::info!("Loop count: {}", loop_count);
 
     let graph = graphs.get(&settings.graph_name)
         .expect("No such graph parsed");
+    if compare_optimal && graph.num_nodes > EXACT_NODE_LIMIT {
+        let err = format!("Graph is too large for the exact strategy: {} nodes, at most {} allowed",
+                          graph.num_nodes, EXACT_NODE_LIMIT);
+        log::error!("{}", err);
+        panic!("{}", err);
+    }
 
     log::info!("Starting benchmarks");
 
@@ -87,10 +142,17 @@ fn main() {
     let mut sum_defended = 0;
     let mut sum_end_time = 0;
     let mut sum_sim_millis = 0;
-    for _ in 0..loop_count {
+    let mut sum_gap = 0;
+    let mut sum_relative_gap = 0.0;
+    let mut num_proven_optimal = 0;
+    for i in 0..loop_count {
         let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name, graph.clone())
             .expect("Invalid strategy specified");
-        let mut problem = OSMFProblem::new(graph.clone(), settings.clone(), strategy)
+        // Use consecutive seeds to make seeded benchmarks reproducible
+        let mut run_settings = settings.clone();
+        run_settings.seed = settings.seed.map(|seed| seed.wrapping_add(i as u64));
+        let problem_settings = run_settings.clone();
+        let mut problem = OSMFProblem::new(graph.clone(), run_settings, strategy)
             .expect("Invalid simulation settings");
 
         problem.simulate();
@@ -100,6 +162,28 @@ fn main() {
         sum_defended += results.nodes_defended;
         sum_end_time += results.end_time;
         sum_sim_millis += results.simulation_time_millis;
+
+        if compare_optimal {
+            let mut exact_settings = problem_settings.clone();
+            exact_settings.strategy_name = "Exact".to_string();
+            exact_settings.strategy_params.clear();
+            let strategy = OSMFStrategy::from_name_and_graph(&exact_settings.strategy_name,
+                                                             graph.clone())
+                .expect("Invalid strategy specified");
+            let mut exact = OSMFProblem::new(graph.clone(), exact_settings, strategy)
+                .expect("Invalid simulation settings");
+            exact.simulate();
+
+            let optimal_burned = exact.simulation_response().nodes_burned;
+            let gap = results.nodes_burned as i64 - optimal_burned as i64;
+            sum_gap += gap;
+            sum_relative_gap += gap as f64 / optimal_burned.max(1) as f64;
+            if let Some(OSMFStrategy::Exact(strategy)) = exact.strategy() {
+                if strategy.is_optimal() {
+                    num_proven_optimal += 1;
+                }
+            }
+        }
     }
 
     let bench_results = BenchResults {
@@ -107,6 +191,11 @@ fn main() {
         avg_def: sum_defended as f64 / loop_count as f64,
         avg_end_time: sum_end_time as f64 / loop_count as f64,
         avg_sim_millis: sum_sim_millis as f64 / loop_count as f64,
+        optimality: compare_optimal.then(|| OptimalityResults {
+            avg_gap: sum_gap as f64 / loop_count as f64,
+            avg_relative_gap: sum_relative_gap / loop_count as f64,
+            num_proven_optimal,
+        }),
     };
 
     log::info!("Benchmark results:\n{:#?}", bench_results);
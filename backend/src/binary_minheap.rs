@@ -1,4 +1,5 @@
 /// Efficient binary min-heap to be used as Dijkstra PQ on FMI graph data
+#[derive(Debug)]
 pub struct BinaryMinHeap {
     heap: Vec<usize>,
     positions: Vec<usize>,
@@ -0,0 +1,244 @@
+use rand::prelude::*;
+
+use crate::binary_minheap::BinaryMinHeap;
+use crate::graph::Graph;
+
+/// Damping factor of the PageRank centrality
+const PAGERANK_DAMPING: f64 = 0.85;
+/// Maximum number of power iterations of the PageRank centrality
+const PAGERANK_MAX_ITERATIONS: usize = 100;
+/// PageRank iterations stop once the ranks change by less than this in total
+const PAGERANK_TOLERANCE: f64 = 1e-10;
+/// Default number of source nodes from which the betweenness is estimated
+pub const DEFAULT_BETWEENNESS_SAMPLES: usize = 64;
+/// Maximum number of nodes of graphs whose exact betweenness or closeness may be computed, as
+/// both take a shortest path search from every node
+pub const EXACT_CENTRALITY_NODE_LIMIT: usize = 10_000;
+
+/// Centrality measure of the nodes of a graph.
+/// Centralities are normalized such that the most central node has centrality `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Centrality {
+    /// Number of shortest paths between all pairs of nodes that pass a node
+    Betweenness,
+    /// Betweenness estimated from the shortest paths of `num_samples` random source nodes
+    SampledBetweenness { num_samples: usize },
+    /// Harmonic closeness, i.e. the sum of the reciprocal distances of a node to all other nodes,
+    /// which is well-defined on disconnected graphs
+    Closeness,
+    /// Stationary distribution of a random walk along the edges that jumps to a random node
+    /// with probability `1 - PAGERANK_DAMPING` in each step
+    PageRank,
+}
+
+impl Centrality {
+    /// Returns true if this centrality takes a shortest path search from every node
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Betweenness | Self::Closeness)
+    }
+
+    /// Get this centrality on a graph with `num_nodes` nodes, on which sampling at least all
+    /// nodes yields the exact betweenness
+    pub fn for_num_nodes(self, num_nodes: usize) -> Self {
+        match self {
+            Self::SampledBetweenness { num_samples } if num_samples >= num_nodes => {
+                Self::Betweenness
+            }
+            _ => self,
+        }
+    }
+}
+
+impl Default for Centrality {
+    fn default() -> Self {
+        Self::SampledBetweenness { num_samples: DEFAULT_BETWEENNESS_SAMPLES }
+    }
+}
+
+/// Compute the centrality `centrality` of all nodes of `graph`
+pub(crate) fn compute(graph: &Graph, centrality: Centrality) -> Vec<f64> {
+    let centralities = match centrality {
+        Centrality::Betweenness => betweenness(graph, 0..graph.num_nodes),
+        Centrality::SampledBetweenness { num_samples } => {
+            // Sources are sampled with a fixed seed, so that cached estimates are reproducible
+            let mut rng = StdRng::seed_from_u64(0);
+            let sources = (0..graph.num_nodes)
+                .choose_multiple(&mut rng, num_samples.min(graph.num_nodes));
+            betweenness(graph, sources.into_iter())
+        }
+        Centrality::Closeness => closeness(graph),
+        Centrality::PageRank => pagerank(graph),
+    };
+
+    let max = centralities.iter().copied().fold(0.0, f64::max);
+    if max > 0.0 {
+        centralities.into_iter().map(|centrality| centrality / max).collect()
+    } else {
+        centralities
+    }
+}
+
+/// Compute the betweenness of all nodes from the shortest paths that start at the nodes in
+/// `sources` with Brandes' algorithm
+fn betweenness<I: Iterator<Item=usize>>(graph: &Graph, sources: I) -> Vec<f64> {
+    let num_nodes = graph.num_nodes;
+    let mut centralities = vec![0.0; num_nodes];
+    let mut dists = vec![usize::MAX; num_nodes];
+    let mut num_paths = vec![0.0; num_nodes];
+    let mut dependencies = vec![0.0; num_nodes];
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); num_nodes];
+    let mut settled = Vec::with_capacity(num_nodes);
+    let mut pq = BinaryMinHeap::with_capacity(num_nodes);
+
+    for src_id in sources {
+        // Count the shortest paths from the source to all nodes
+        dists[src_id] = 0;
+        num_paths[src_id] = 1.0;
+        pq.push(src_id, &dists);
+        while !pq.is_empty() {
+            let node_id = pq.pop(&dists);
+            settled.push(node_id);
+            for edge in graph.get_outgoing_edges(node_id) {
+                let dist = dists[node_id] + edge.dist;
+                if dist < dists[edge.tgt] {
+                    dists[edge.tgt] = dist;
+                    num_paths[edge.tgt] = num_paths[node_id];
+                    preds[edge.tgt].clear();
+                    preds[edge.tgt].push(node_id);
+                    if pq.contains(edge.tgt) {
+                        pq.decrease_key(edge.tgt, &dists);
+                    } else {
+                        pq.push(edge.tgt, &dists);
+                    }
+                } else if dist == dists[edge.tgt] {
+                    num_paths[edge.tgt] += num_paths[node_id];
+                    preds[edge.tgt].push(node_id);
+                }
+            }
+        }
+
+        // Accumulate the dependencies in order of decreasing distance and reset all nodes
+        while let Some(node_id) = settled.pop() {
+            for &pred in &preds[node_id] {
+                dependencies[pred] += num_paths[pred] / num_paths[node_id]
+                    * (1.0 + dependencies[node_id]);
+            }
+            if node_id != src_id {
+                centralities[node_id] += dependencies[node_id];
+            }
+            dists[node_id] = usize::MAX;
+            num_paths[node_id] = 0.0;
+            dependencies[node_id] = 0.0;
+            preds[node_id].clear();
+        }
+    }
+
+    centralities
+}
+
+/// Compute the harmonic closeness of all nodes
+fn closeness(graph: &Graph) -> Vec<f64> {
+    (0..graph.num_nodes)
+        .map(|node_id| graph.run_dijkstra(&[node_id]).into_iter()
+            .filter(|&dist| dist > 0 && dist < usize::MAX)
+            .map(|dist| 1.0 / dist as f64)
+            .sum())
+        .collect()
+}
+
+/// Compute the PageRank of all nodes with the power method. The ranks of nodes without outgoing
+/// edges are distributed evenly among all nodes.
+fn pagerank(graph: &Graph) -> Vec<f64> {
+    let num_nodes = graph.num_nodes;
+    let mut ranks = vec![1.0 / num_nodes as f64; num_nodes];
+    for _ in 0..PAGERANK_MAX_ITERATIONS {
+        let dangling_rank: f64 = (0..num_nodes)
+            .filter(|&node_id| graph.get_outgoing_edges(node_id).is_empty())
+            .map(|node_id| ranks[node_id])
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING + PAGERANK_DAMPING * dangling_rank) / num_nodes as f64;
+        let mut next_ranks = vec![base; num_nodes];
+        for (node_id, &rank) in ranks.iter().enumerate() {
+            let edges = graph.get_outgoing_edges(node_id);
+            for edge in edges {
+                next_ranks[edge.tgt] += PAGERANK_DAMPING * rank / edges.len() as f64;
+            }
+        }
+
+        let change: f64 = ranks.iter().zip(&next_ranks)
+            .map(|(rank, next_rank)| (rank - next_rank).abs())
+            .sum();
+        ranks = next_ranks;
+        if change < PAGERANK_TOLERANCE {
+            break;
+        }
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod test {
+    use crate::centrality::{self, Centrality};
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_centralities() {
+        let graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        let num_nodes = graph.num_nodes;
+
+        // A node has a positive betweenness iff it lies on a shortest path between two other nodes
+        let betweenness = graph.centrality(Centrality::Betweenness);
+        assert_eq!(betweenness.len(), num_nodes);
+        assert!(betweenness.iter().all(|&c| (0.0..=1.0).contains(&c)));
+        assert!(betweenness.iter().any(|&c| c == 1.0));
+        let dists: Vec<_> = (0..num_nodes).map(|node_id| graph.run_dijkstra(&[node_id])).collect();
+        for node_id in 0..num_nodes {
+            let is_between = (0..num_nodes).any(|src| (0..num_nodes).any(|tgt|
+                src != node_id && tgt != node_id && src != tgt
+                    && dists[src][node_id] < usize::MAX && dists[node_id][tgt] < usize::MAX
+                    && dists[src][node_id] + dists[node_id][tgt] == dists[src][tgt]));
+            assert_eq!(betweenness[node_id] > 0.0, is_between);
+        }
+
+        // Sampling all nodes yields the exact betweenness
+        let sampled = graph.centrality(Centrality::SampledBetweenness { num_samples: num_nodes });
+        assert!(betweenness.iter().zip(sampled.iter()).all(|(c1, c2)| (c1 - c2).abs() < 1e-9));
+        let all_samples = Centrality::SampledBetweenness { num_samples: num_nodes };
+        assert_eq!(all_samples.for_num_nodes(num_nodes), Centrality::Betweenness);
+
+        // Only the last sample count is cached
+        let two_samples = Centrality::SampledBetweenness { num_samples: 2 };
+        let sampled = graph.centrality(two_samples);
+        for num_samples in 3..20 {
+            graph.centrality(Centrality::SampledBetweenness { num_samples });
+        }
+        assert_eq!(graph.num_cached_centralities(), 2);
+        assert!(!std::sync::Arc::ptr_eq(&sampled, &graph.centrality(two_samples)));
+
+        let closeness = graph.centrality(Centrality::Closeness);
+        let harmonic: Vec<f64> = dists.iter()
+            .map(|dists| dists.iter()
+                .filter(|&&dist| dist > 0 && dist < usize::MAX)
+                .map(|&dist| 1.0 / dist as f64)
+                .sum())
+            .collect();
+        let max_harmonic = harmonic.iter().copied().fold(0.0, f64::max);
+        assert!(closeness.iter().zip(&harmonic)
+            .all(|(c, h)| (c - h / max_harmonic).abs() < 1e-9));
+
+        // PageRank is a fixed point of the random walk with jumps
+        let pagerank = centrality::pagerank(&graph);
+        assert!((pagerank.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        for node_id in 0..num_nodes {
+            let incoming: f64 = graph.edges().iter()
+                .filter(|edge| edge.tgt == node_id)
+                .map(|edge| pagerank[edge.src] / graph.get_outgoing_edges(edge.src).len() as f64)
+                .sum();
+            assert!((pagerank[node_id] - (0.15 / num_nodes as f64 + 0.85 * incoming)).abs() < 1e-6);
+        }
+
+        // Centralities are cached per graph
+        assert!(std::sync::Arc::ptr_eq(&graph.centrality(Centrality::PageRank),
+                                       &graph.centrality(Centrality::PageRank)));
+    }
+}
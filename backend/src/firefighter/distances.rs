@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use crate::binary_minheap::BinaryMinHeap;
+use crate::firefighter::problem::NodeDataStorage;
+use crate::graph::Graph;
+
+/// Shortest distances from a set of source nodes that avoid a set of blocked nodes, e.g. the
+/// distances of all nodes to the fire that cannot pass defended nodes.
+/// During a simulation both sets only grow, so the distances are updated incrementally from
+/// round to round instead of being recomputed from scratch: new sources only shorten distances
+/// near them and new blocked nodes only lengthen the distances of the nodes whose shortest
+/// paths pass them.
+/// Instances created by `without_blocking` never block defended nodes and yield the plain
+/// shortest distances from the sources.
+#[derive(Debug)]
+pub(super) struct IncrementalDistances {
+    graph: Arc<Graph>,
+    /// Incoming edges of all nodes as tuples of the edge source and the edge distance.
+    /// The incoming edges of node `v` are stored at `in_offsets[v]..in_offsets[v + 1]`.
+    in_edges: Vec<(usize, usize)>,
+    in_offsets: Vec<usize>,
+    dists: Vec<usize>,
+    preds: Vec<usize>,
+    /// Initial distance of each source node, or `usize::MAX` for all other nodes
+    source_offsets: Vec<usize>,
+    num_sources: usize,
+    is_blocked: Vec<bool>,
+    blocked: Vec<usize>,
+    /// Block the defended nodes
+    blocks_defended: bool,
+    pq: BinaryMinHeap,
+}
+
+impl IncrementalDistances {
+    /// Create a new instance without sources, in which no node is reachable
+    pub(super) fn new(graph: Arc<Graph>) -> Self {
+        let num_nodes = graph.num_nodes;
+        let mut in_offsets = vec![0; num_nodes + 1];
+        for edge in graph.edges() {
+            in_offsets[edge.tgt + 1] += 1;
+        }
+        for i in 0..num_nodes {
+            in_offsets[i + 1] += in_offsets[i];
+        }
+        let mut next = in_offsets.clone();
+        let mut in_edges = vec![(0, 0); graph.num_edges];
+        for edge in graph.edges() {
+            in_edges[next[edge.tgt]] = (edge.src, edge.dist);
+            next[edge.tgt] += 1;
+        }
+
+        Self {
+            in_edges,
+            in_offsets,
+            dists: vec![usize::MAX; num_nodes],
+            preds: vec![usize::MAX; num_nodes],
+            source_offsets: vec![usize::MAX; num_nodes],
+            num_sources: 0,
+            is_blocked: vec![false; num_nodes],
+            blocked: Vec::new(),
+            blocks_defended: true,
+            pq: BinaryMinHeap::with_capacity(num_nodes),
+            graph,
+        }
+    }
+
+    /// Create a new instance without sources, whose distances pass defended nodes
+    pub(super) fn without_blocking(graph: Arc<Graph>) -> Self {
+        Self {
+            blocks_defended: false,
+            ..Self::new(graph)
+        }
+    }
+
+    /// Get the distances of all nodes, which are `usize::MAX` for unreachable nodes
+    pub(super) fn dists(&self) -> &[usize] {
+        &self.dists
+    }
+
+    /// Update the distances to the distinct source nodes in `sources`, which are given as tuples
+    /// of the source node id and the initial distance of the source node, in the graph without
+    /// the defended nodes in `node_data` unless this instance does not block them.
+    /// The distances are recomputed from scratch if a previous source is missing in `sources`
+    /// or a previously blocked node is not defended anymore, e.g. when the same strategy
+    /// instance contains the fire in different copies of a simulation.
+    pub(super) fn update(&mut self, sources: &[(usize, usize)], node_data: &NodeDataStorage) {
+        let num_known = sources.iter()
+            .filter(|&&(node_id, offset)| self.source_offsets[node_id] == offset)
+            .count();
+        if num_known < self.num_sources
+            || self.blocked.iter().any(|node_id| !node_data.is_defended(node_id)) {
+            self.reset();
+        }
+
+        if self.blocks_defended {
+            let defended: Vec<_> = node_data.get_defended().into_iter()
+                .filter(|&node_id| !self.is_blocked[node_id])
+                .collect();
+            self.block(&defended);
+        }
+        self.add_sources(sources);
+    }
+
+    /// Remove all sources and blocked nodes
+    fn reset(&mut self) {
+        self.dists.iter_mut().for_each(|dist| *dist = usize::MAX);
+        self.preds.iter_mut().for_each(|pred| *pred = usize::MAX);
+        self.source_offsets.iter_mut().for_each(|offset| *offset = usize::MAX);
+        self.num_sources = 0;
+        for &node_id in &self.blocked {
+            self.is_blocked[node_id] = false;
+        }
+        self.blocked.clear();
+    }
+
+    /// Add the source nodes in `sources` that are neither sources already nor blocked
+    fn add_sources(&mut self, sources: &[(usize, usize)]) {
+        for &(node_id, offset) in sources {
+            if self.is_blocked[node_id] || self.source_offsets[node_id] == offset {
+                continue;
+            }
+            if self.source_offsets[node_id] == usize::MAX {
+                self.num_sources += 1;
+            }
+            self.source_offsets[node_id] = self.source_offsets[node_id].min(offset);
+            if offset < self.dists[node_id] {
+                self.dists[node_id] = offset;
+                self.preds[node_id] = usize::MAX;
+                self.push(node_id);
+            }
+        }
+        self.propagate();
+    }
+
+    /// Block the nodes in `nodes` and repair the distances of all nodes whose shortest paths
+    /// pass them
+    fn block(&mut self, nodes: &[usize]) {
+        if nodes.is_empty() {
+            return;
+        }
+
+        // Invalidate the shortest path subtrees below the blocked nodes
+        let mut stack = Vec::new();
+        for &node_id in nodes {
+            self.is_blocked[node_id] = true;
+            self.blocked.push(node_id);
+            self.dists[node_id] = usize::MAX;
+            self.preds[node_id] = usize::MAX;
+            stack.push(node_id);
+        }
+        let mut affected = Vec::new();
+        while let Some(node_id) = stack.pop() {
+            for edge in self.graph.get_outgoing_edges(node_id) {
+                if self.preds[edge.tgt] == node_id && !self.is_blocked[edge.tgt] {
+                    self.dists[edge.tgt] = self.source_offsets[edge.tgt];
+                    self.preds[edge.tgt] = usize::MAX;
+                    affected.push(edge.tgt);
+                    stack.push(edge.tgt);
+                }
+            }
+        }
+
+        // Reconnect the invalidated nodes to the nodes with valid distances
+        for &node_id in &affected {
+            for &(src, dist) in &self.in_edges[self.in_offsets[node_id]..self.in_offsets[node_id + 1]] {
+                if self.dists[src] < usize::MAX && self.dists[src] + dist < self.dists[node_id] {
+                    self.dists[node_id] = self.dists[src] + dist;
+                    self.preds[node_id] = src;
+                }
+            }
+            if self.dists[node_id] < usize::MAX {
+                self.push(node_id);
+            }
+        }
+        self.propagate();
+    }
+
+    /// Push `node_id` on the queue or decrease its key
+    fn push(&mut self, node_id: usize) {
+        if self.pq.contains(node_id) {
+            self.pq.decrease_key(node_id, &self.dists);
+        } else {
+            self.pq.push(node_id, &self.dists);
+        }
+    }
+
+    /// Relax the edges of all queued nodes until all distances are shortest distances again
+    fn propagate(&mut self) {
+        let graph = self.graph.clone();
+        while !self.pq.is_empty() {
+            let node_id = self.pq.pop(&self.dists);
+            for edge in graph.get_outgoing_edges(node_id) {
+                if self.is_blocked[edge.tgt] {
+                    continue;
+                }
+                let dist = self.dists[node_id] + edge.dist;
+                if dist < self.dists[edge.tgt] {
+                    self.dists[edge.tgt] = dist;
+                    self.preds[edge.tgt] = node_id;
+                    self.push(edge.tgt);
+                }
+            }
+        }
+    }
+}
+
+impl Default for IncrementalDistances {
+    fn default() -> Self {
+        Self::new(Arc::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use rand::prelude::*;
+
+    use crate::firefighter::distances::IncrementalDistances;
+    use crate::firefighter::problem::{NodeDataStorage, OSMFDefenseMode};
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_incremental_distances() {
+        let graph = Arc::new(Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap());
+        let mut distances = IncrementalDistances::new(graph.clone());
+        let mut node_data = NodeDataStorage::new(OSMFDefenseMode::Nodes, None);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut sources = Vec::new();
+
+        for round in 0..20 {
+            // Add sources and defend other nodes
+            for _ in 0..3 {
+                let node_id = rng.gen_range(0..graph.num_nodes);
+                if !node_data.is_defended(&node_id)
+                    && !sources.iter().any(|&(src_id, _)| src_id == node_id) {
+                    sources.push((node_id, round));
+                }
+            }
+            let defended: Vec<_> = (0..10)
+                .map(|_| rng.gen_range(0..graph.num_nodes))
+                .filter(|&node_id| !sources.iter().any(|&(src_id, _)| src_id == node_id))
+                .filter(|node_id| !node_data.is_defended(node_id))
+                .collect();
+            node_data.mark_defended(&defended, round as u64);
+
+            distances.update(&sources, &node_data);
+            let (expected, _) = graph.run_dijkstra_with_offsets_and_filter(&sources, |edge|
+                !node_data.is_defended(&edge.tgt));
+            assert_eq!(distances.dists(), expected.as_slice());
+        }
+
+        // Removing a source recomputes the distances
+        sources.remove(0);
+        distances.update(&sources, &node_data);
+        let (expected, _) = graph.run_dijkstra_with_offsets_and_filter(&sources, |edge|
+            !node_data.is_defended(&edge.tgt));
+        assert_eq!(distances.dists(), expected.as_slice());
+
+        // Distances without blocking pass the defended nodes
+        let mut unblocked = IncrementalDistances::without_blocking(graph.clone());
+        unblocked.update(&sources, &node_data);
+        assert_eq!(unblocked.dists(), graph.run_dijkstra_with_offsets(&sources).as_slice());
+        assert_ne!(unblocked.dists(), distances.dists());
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Serialize, Serializer};
+
+/// Ordered map that can be forked cheaply.
+/// A fork shares the entries of the original map and only stores the entries that are inserted
+/// into it afterwards. The original map is changed in place as long as no fork shares its
+/// entries, e.g. once the forks that looked ahead are dropped again.
+#[derive(Debug, Clone)]
+pub(super) struct LayeredMap<K, V> {
+    /// Entries that may be shared with forks
+    base: Arc<BTreeMap<K, V>>,
+    /// Entries that were inserted while the base was shared
+    overlay: BTreeMap<K, V>,
+    len: usize,
+}
+
+impl<K: Ord + Clone, V: Clone> LayeredMap<K, V> {
+    /// Create a new empty map
+    pub(super) fn new() -> Self {
+        Self {
+            base: Arc::new(BTreeMap::new()),
+            overlay: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Create a fork of this map that shares its entries
+    pub(super) fn fork(&self) -> Self {
+        let base = if self.overlay.is_empty() {
+            self.base.clone()
+        } else {
+            Arc::new(self.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+        };
+        Self {
+            base,
+            overlay: BTreeMap::new(),
+            len: self.len,
+        }
+    }
+
+    /// Get the value of `key`
+    pub(super) fn get(&self, key: &K) -> Option<&V> {
+        self.overlay.get(key).or_else(|| self.base.get(key))
+    }
+
+    /// Returns true if this map contains `key`
+    pub(super) fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Insert `value` for `key`, replacing the previous value of `key`
+    pub(super) fn insert(&mut self, key: K, value: V) {
+        if !self.contains_key(&key) {
+            self.len += 1;
+        }
+        match Arc::get_mut(&mut self.base) {
+            Some(base) => {
+                base.append(&mut self.overlay);
+                base.insert(key, value);
+            }
+            None => {
+                self.overlay.insert(key, value);
+            }
+        }
+    }
+
+    /// Get the number of entries
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get all entries in increasing order of their keys
+    pub(super) fn iter(&self) -> impl Iterator<Item=(&K, &V)> {
+        let mut base = self.base.iter().peekable();
+        let mut overlay = self.overlay.iter().peekable();
+        std::iter::from_fn(move || {
+            let order = match (base.peek(), overlay.peek()) {
+                (Some((base_key, _)), Some((overlay_key, _))) => base_key.cmp(overlay_key),
+                (Some(_), None) => Ordering::Less,
+                (None, _) => Ordering::Greater,
+            };
+            match order {
+                Ordering::Less => base.next(),
+                Ordering::Equal => {
+                    // Inserted entries replace the shared entries
+                    base.next();
+                    overlay.next()
+                }
+                Ordering::Greater => overlay.next(),
+            }
+        })
+    }
+
+    /// Get all keys in increasing order
+    pub(super) fn keys(&self) -> impl Iterator<Item=&K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Get all values in increasing order of their keys
+    pub(super) fn values(&self) -> impl Iterator<Item=&V> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Default for LayeredMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone + Serialize, V: Clone + Serialize> Serialize for LayeredMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::firefighter::layered::LayeredMap;
+
+    #[test]
+    fn test_layered_map() {
+        let mut map = LayeredMap::new();
+        for key in [3, 1, 5] {
+            map.insert(key, key * 10);
+        }
+
+        // A fork shares the entries and only stores its own insertions
+        let mut fork = map.fork();
+        fork.insert(2, 20);
+        fork.insert(5, 51);
+        assert_eq!(fork.base.len(), 3);
+        assert_eq!(fork.overlay.len(), 2);
+        assert_eq!(fork.len(), 4);
+        assert_eq!(fork.iter().collect::<Vec<_>>(), vec![(&1, &10), (&2, &20), (&3, &30), (&5, &51)]);
+        assert_eq!(fork.get(&5), Some(&51));
+        assert!(!fork.contains_key(&4));
+
+        // The original map is not affected and changes in place once the fork is dropped
+        map.insert(4, 40);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 3, 4, 5]);
+        assert_eq!(map.get(&5), Some(&50));
+        drop(fork);
+        map.insert(6, 60);
+        assert!(map.overlay.is_empty());
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), vec![10, 30, 40, 50, 60]);
+        assert_eq!(map.len(), 5);
+
+        // Forks of forks contain the entries of both
+        let mut fork = map.fork();
+        fork.insert(7, 70);
+        let nested = fork.fork();
+        assert_eq!(nested.len(), 6);
+        assert_eq!(nested.get(&7), Some(&70));
+        assert_eq!(nested.get(&1), Some(&10));
+    }
+}
\ No newline at end of file
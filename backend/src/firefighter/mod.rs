@@ -1,5 +1,12 @@
+mod distances;
+mod layered;
+pub mod monte_carlo;
+pub mod parameters;
 pub mod problem;
+pub mod record;
+pub mod registry;
 pub mod strategy;
+mod tree;
 mod view;
 
 /// `u64` type alias to denote a time unit in the firefighter problem
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rand::prelude::*;
+use serde::Serialize;
+
+use crate::firefighter::problem::{OSMFProblem, OSMFSettings, OSMFSettingsError};
+use crate::firefighter::strategy::OSMFStrategy;
+use crate::graph::Graph;
+
+/// Distribution of a count over multiple simulation runs
+#[derive(Debug, Serialize)]
+pub struct OSMFDistribution {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub median: f64,
+    /// Maps each observed value to the number of runs in which it was observed
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+impl OSMFDistribution {
+    /// Compute the distribution of the values in `samples`.
+    /// `samples` must not be empty.
+    fn from_samples(samples: &[usize]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<usize>() as f64 / n;
+        let variance = sorted.iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>() / n;
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 1 {
+            sorted[mid] as f64
+        } else {
+            (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+        };
+
+        let mut histogram = BTreeMap::new();
+        for &x in &sorted {
+            *histogram.entry(x).or_insert(0) += 1;
+        }
+
+        Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            std_dev: variance.sqrt(),
+            median,
+            histogram,
+        }
+    }
+}
+
+/// Container for the aggregated results of a Monte Carlo simulation
+#[derive(Debug, Serialize)]
+pub struct OSMFMonteCarloResponse {
+    pub num_runs: usize,
+    /// Seed of the first run. Run `i` uses seed `base_seed + i`.
+    pub base_seed: u64,
+    pub nodes_burned: OSMFDistribution,
+    pub nodes_defended: OSMFDistribution,
+    pub end_time: OSMFDistribution,
+    pub simulation_time_millis: u128,
+}
+
+/// Simulate the firefighter problem described by `settings` `num_runs` times with
+/// consecutive seeds and aggregate the distribution of the results.
+/// If `settings` does not specify a seed, a random base seed is chosen.
+pub fn simulate_monte_carlo(graph: Arc<Graph>, settings: &OSMFSettings, num_runs: usize)
+                            -> Result<OSMFMonteCarloResponse, OSMFSettingsError> {
+    if num_runs == 0 {
+        return Err(OSMFSettingsError::InvalidNumRuns { num_runs });
+    }
+
+    let base_seed = settings.seed.unwrap_or_else(|| thread_rng().gen());
+    log::info!("Starting Monte Carlo simulation. num_runs={}, base_seed={}.", num_runs, base_seed);
+
+    let start = Instant::now();
+
+    let mut burned = Vec::with_capacity(num_runs);
+    let mut defended = Vec::with_capacity(num_runs);
+    let mut end_times = Vec::with_capacity(num_runs);
+    for i in 0..num_runs {
+        let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name, graph.clone())
+            .ok_or_else(|| OSMFSettingsError::UnknownStrategy {
+                strategy_name: settings.strategy_name.clone(),
+            })?;
+        let mut run_settings = settings.clone();
+        run_settings.seed = Some(base_seed.wrapping_add(i as u64));
+
+        let mut problem = OSMFProblem::new(graph.clone(), run_settings, strategy)?;
+        problem.simulate();
+
+        let response = problem.simulation_response();
+        burned.push(response.nodes_burned);
+        defended.push(response.nodes_defended);
+        end_times.push(response.end_time as usize);
+    }
+
+    log::info!("Finished Monte Carlo simulation");
+
+    Ok(OSMFMonteCarloResponse {
+        num_runs,
+        base_seed,
+        nodes_burned: OSMFDistribution::from_samples(&burned),
+        nodes_defended: OSMFDistribution::from_samples(&defended),
+        end_time: OSMFDistribution::from_samples(&end_times),
+        simulation_time_millis: start.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::firefighter::monte_carlo::{OSMFDistribution, simulate_monte_carlo};
+    use crate::firefighter::problem::{OSMFSettings, OSMFSpreadModel};
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_distribution() {
+        let dist = OSMFDistribution::from_samples(&[4, 1, 3, 1]);
+
+        assert_eq!(dist.min, 1);
+        assert_eq!(dist.max, 4);
+        assert_eq!(dist.mean, 2.25);
+        assert_eq!(dist.median, 2.0);
+        assert_eq!(dist.histogram.get(&1), Some(&2));
+        assert_eq!(dist.histogram.values().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let graph = Arc::new(Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap());
+        let settings = OSMFSettings {
+            graph_name: "bbgrund".to_string(),
+            strategy_name: "Greedy".to_string(),
+            num_roots: 3,
+            num_ffs: 2,
+            strategy_every: 10,
+            seed: Some(42),
+            spread_model: OSMFSpreadModel::Probabilistic {
+                ignition_probability: 0.7,
+                delay: None,
+            },
+            ..OSMFSettings::default()
+        };
+
+        let res1 = simulate_monte_carlo(graph.clone(), &settings, 5).unwrap();
+        let res2 = simulate_monte_carlo(graph, &settings, 5).unwrap();
+
+        assert_eq!(res1.nodes_burned.histogram, res2.nodes_burned.histogram);
+        assert_eq!(res1.nodes_defended.histogram, res2.nodes_defended.histogram);
+        assert_eq!(res1.nodes_burned.histogram.values().sum::<usize>(), 5);
+    }
+}
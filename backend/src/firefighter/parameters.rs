@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::firefighter::problem::OSMFSettingsError;
+
+/// Value of a strategy parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OSMFParameterValue {
+    Integer(i64),
+    Float(f64),
+    Choice(String),
+}
+
+impl fmt::Display for OSMFParameterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Choice(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl FromStr for OSMFParameterValue {
+    type Err = std::convert::Infallible;
+
+    /// Parse an integer, a float or otherwise a choice
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.parse() {
+            Ok(Self::Integer(value))
+        } else if let Ok(value) = s.parse() {
+            Ok(Self::Float(value))
+        } else {
+            Ok(Self::Choice(s.to_string()))
+        }
+    }
+}
+
+/// Type and range of values of a strategy parameter
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum OSMFParameterType {
+    /// Integer in `[min, max]`
+    Integer { min: i64, max: i64 },
+    /// Finite float in `[min, max]`. Integers are converted to floats.
+    Float { min: f64, max: f64 },
+    /// One of the strings in `choices`
+    Choice { choices: Vec<&'static str> },
+}
+
+/// Declaration of a tunable strategy parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct OSMFParameterSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    #[serde(flatten)]
+    pub param_type: OSMFParameterType,
+    pub default: OSMFParameterValue,
+}
+
+impl OSMFParameterSpec {
+    /// Check whether `value` is a valid value of this parameter and return it converted to
+    /// the type of this parameter
+    fn validate(&self, value: &OSMFParameterValue) -> Result<OSMFParameterValue, String> {
+        match (&self.param_type, value) {
+            (&OSMFParameterType::Integer { min, max }, &OSMFParameterValue::Integer(value)) => {
+                if (min..=max).contains(&value) {
+                    return Ok(OSMFParameterValue::Integer(value));
+                }
+                Err(format!("{} must be in [{}, {}]: {}", self.name, min, max, value))
+            }
+            (&OSMFParameterType::Float { min, max }, &OSMFParameterValue::Integer(value)) => {
+                self.validate(&OSMFParameterValue::Float(value as f64))
+                    .map_err(|_| format!("{} must be in [{}, {}]: {}", self.name, min, max, value))
+            }
+            (&OSMFParameterType::Float { min, max }, &OSMFParameterValue::Float(value)) => {
+                if value.is_finite() && (min..=max).contains(&value) {
+                    return Ok(OSMFParameterValue::Float(value));
+                }
+                Err(format!("{} must be in [{}, {}]: {}", self.name, min, max, value))
+            }
+            (OSMFParameterType::Choice { choices }, OSMFParameterValue::Choice(value)) => {
+                if choices.contains(&value.as_str()) {
+                    return Ok(OSMFParameterValue::Choice(value.clone()));
+                }
+                Err(format!("{} must be one of {:?}: {}", self.name, choices, value))
+            }
+            _ => Err(format!("{} must be of type {:?}: {:?}", self.name, self.param_type, value)),
+        }
+    }
+}
+
+/// Fire containment strategy together with its tunable parameters
+#[derive(Debug, Clone, Serialize)]
+pub struct OSMFStrategyInfo {
+    pub name: String,
+    pub parameters: Vec<OSMFParameterSpec>,
+}
+
+/// Validated values of all parameters of a strategy.
+/// Parameters that are not set explicitly have their default value.
+#[derive(Debug, Clone, Default)]
+pub struct OSMFParameters {
+    values: BTreeMap<&'static str, OSMFParameterValue>,
+}
+
+impl OSMFParameters {
+    /// Validate the parameter values `values` of strategy `strategy_name` against the parameter
+    /// declarations `schema`
+    pub fn resolve(strategy_name: &str, schema: &[OSMFParameterSpec],
+                   values: &BTreeMap<String, OSMFParameterValue>) -> Result<Self, OSMFSettingsError> {
+        let invalid = |reason: String| OSMFSettingsError::InvalidStrategyParameter {
+            strategy_name: strategy_name.to_string(),
+            reason,
+        };
+
+        if let Some(name) = values.keys().find(|&name| !schema.iter().any(|spec| spec.name == name)) {
+            return Err(invalid(format!("unknown parameter {}", name)));
+        }
+
+        let mut resolved = BTreeMap::new();
+        for spec in schema {
+            let value = match values.get(spec.name) {
+                Some(value) => spec.validate(value).map_err(invalid)?,
+                None => spec.default.clone(),
+            };
+            resolved.insert(spec.name, value);
+        }
+
+        Ok(Self {
+            values: resolved,
+        })
+    }
+
+    /// Get the value of the integer parameter `name`
+    pub fn integer(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(&OSMFParameterValue::Integer(value)) => value,
+            _ => panic!("No integer parameter {}", name),
+        }
+    }
+
+    /// Get the value of the float parameter `name`
+    pub fn float(&self, name: &str) -> f64 {
+        match self.values.get(name) {
+            Some(&OSMFParameterValue::Float(value)) => value,
+            _ => panic!("No float parameter {}", name),
+        }
+    }
+
+    /// Get the value of the choice parameter `name`
+    pub fn choice(&self, name: &str) -> &str {
+        match self.values.get(name) {
+            Some(OSMFParameterValue::Choice(value)) => value,
+            _ => panic!("No choice parameter {}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::firefighter::parameters::{OSMFParameters, OSMFParameterSpec, OSMFParameterType,
+                                         OSMFParameterValue};
+
+    #[test]
+    fn test_resolve() {
+        let schema = vec![
+            OSMFParameterSpec {
+                name: "weight",
+                description: "",
+                param_type: OSMFParameterType::Float { min: 0.0, max: 1.0 },
+                default: OSMFParameterValue::Float(0.5),
+            },
+            OSMFParameterSpec {
+                name: "mode",
+                description: "",
+                param_type: OSMFParameterType::Choice { choices: vec!["A", "B"] },
+                default: OSMFParameterValue::Choice("A".to_string()),
+            },
+        ];
+        let resolve = |values: &[(&str, &str)]| {
+            let values: BTreeMap<_, _> = values.iter()
+                .map(|&(name, value)| (name.to_string(), value.parse().unwrap()))
+                .collect();
+            OSMFParameters::resolve("Test", &schema, &values)
+        };
+
+        let params = resolve(&[]).unwrap();
+        assert_eq!(params.float("weight"), 0.5);
+        assert_eq!(params.choice("mode"), "A");
+
+        let params = resolve(&[("weight", "1"), ("mode", "B")]).unwrap();
+        assert_eq!(params.float("weight"), 1.0);
+        assert_eq!(params.choice("mode"), "B");
+
+        assert!(resolve(&[("weight", "1.5")]).is_err());
+        assert!(resolve(&[("weight", "NaN")]).is_err());
+        assert!(resolve(&[("mode", "C")]).is_err());
+        assert!(resolve(&[("mode", "1")]).is_err());
+        assert!(resolve(&[("unknown", "1")]).is_err());
+    }
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::Instant;
@@ -6,60 +6,836 @@ use std::time::Instant;
 use derive_more::{Display, Error};
 use log;
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use serde::{Serialize, Deserialize};
 
-use crate::firefighter::strategy::OSMFStrategy;
+use crate::firefighter::layered::LayeredMap;
+use crate::firefighter::parameters::OSMFParameterValue;
+use crate::firefighter::record::{OSMFGraphIdentity, OSMFRecordError, OSMFSimulationRecord,
+                                  RECORD_VERSION};
+use crate::firefighter::strategy::{self, OSMFStrategy};
 use crate::firefighter::TimeUnit;
 use crate::firefighter::view::{View, Coords};
-use crate::graph::{Graph, GridBounds};
+use crate::graph::{Edge, Graph, GridBounds, Node};
+
+/// Maximum mean of geometrically distributed spread delays
+pub const MAX_GEOMETRIC_DELAY_MEAN: f64 = 1e6;
+/// Maximum strength of the wind, which scales spread delays by a factor of at most
+/// `exp(MAX_WIND_STRENGTH)`
+pub const MAX_WIND_STRENGTH: f64 = 10.0;
+
+/// Distribution of the random delay that is added to the spread time of an edge
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum OSMFDelayDistribution {
+    /// Delay uniformly distributed in `[min, max]`
+    Uniform { min: TimeUnit, max: TimeUnit },
+    /// Geometrically distributed delay with mean `mean`
+    Geometric { mean: f64 },
+}
+
+impl OSMFDelayDistribution {
+    /// Draw a random delay from this distribution
+    fn sample<R: Rng>(&self, rng: &mut R) -> TimeUnit {
+        match *self {
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+            Self::Geometric { mean } => {
+                if mean <= 0.0 {
+                    return 0;
+                }
+                // Number of failed trials before the first success with success
+                // probability `1 / (1 + mean)`
+                let p = 1.0 / (1.0 + mean);
+                let u: f64 = 1.0 - rng.gen::<f64>();
+                (u.ln() / (1.0 - p).ln()).floor() as TimeUnit
+            }
+        }
+    }
+}
+
+/// Model that determines how the fire spreads along the graph edges
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(tag = "type")]
+pub enum OSMFSpreadModel {
+    /// The fire spreads along every undefended edge after `edge.dist` time units
+    #[default]
+    Deterministic,
+    /// The fire spreads along every undefended edge with probability `ignition_probability`
+    /// after `edge.dist` time units plus an optional random delay
+    Probabilistic {
+        ignition_probability: f64,
+        #[serde(default)]
+        delay: Option<OSMFDelayDistribution>,
+    },
+}
+
+/// Wind that affects the spread of the fire from time `time` on until the next
+/// entry of the wind schedule becomes active
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFWind {
+    #[serde(default)]
+    pub time: TimeUnit,
+    /// Direction the wind blows to in degrees clockwise from north
+    pub direction: f64,
+    /// Non-negative strength of the wind. A strength of `0` means no wind.
+    pub strength: f64,
+}
+
+impl OSMFWind {
+    /// Get the factor by which the spread delay of an edge with bearing `bearing` is scaled.
+    /// The factor is `exp(-strength * cos(angle))`, where `angle` is the angle between the
+    /// edge and the wind direction. Hence, the fire spreads faster downwind and slower upwind.
+    pub fn spread_factor(&self, bearing: f64) -> f64 {
+        let angle = (bearing - self.direction).to_radians();
+        (-self.strength * angle.cos()).exp()
+    }
+}
+
+/// Mobility model for firefighters
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFMobility {
+    /// Nodes at which the firefighters are stationed initially.
+    /// Firefighters are assigned to the stations in a round robin manner.
+    pub stations: Vec<usize>,
+    /// Distance that a firefighter travels per time unit
+    pub speed: f64,
+}
+
+/// A scheduled ignition of a node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFIgnition {
+    pub time: TimeUnit,
+    pub node_id: usize,
+}
+
+/// Secondary ignitions, such as spot fires, that occur during a running simulation
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OSMFIgnitions {
+    #[serde(default)]
+    pub scheduled: Vec<OSMFIgnition>,
+    /// Probability that a random undefended node ignites in a time step
+    #[serde(default)]
+    pub random_probability: f64,
+    /// If set, random ignitions only occur within this distance of the fire
+    #[serde(default)]
+    pub spot_distance: Option<usize>,
+}
+
+/// Rectangular region given by latitude and longitude bounds
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFRegion {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+}
+
+impl OSMFRegion {
+    /// Returns true if `node` is located within this region
+    pub fn contains(&self, node: &Node) -> bool {
+        (self.min_lat..=self.max_lat).contains(&node.lat)
+            && (self.min_lon..=self.max_lon).contains(&node.lon)
+    }
+}
+
+/// Critical nodes, such as hospitals or schools, that should be protected from the fire
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OSMFTargets {
+    #[serde(default)]
+    pub nodes: Vec<usize>,
+    /// Optional region in which all nodes are targets
+    #[serde(default)]
+    pub region: Option<OSMFRegion>,
+}
+
+/// Static strategy that chooses firebreaks before the fire starts
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OSMFFirebreakStrategy {
+    /// Choose the most important nodes with respect to the objective, i.e. the nodes with the
+    /// highest degree or weight
+    #[default]
+    MostImportant,
+    /// Choose random nodes
+    Random,
+}
+
+/// Firebreaks, i.e. nodes that are defended at time `0` before any fire starts
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OSMFFirebreaks {
+    /// Explicitly given firebreak nodes
+    #[serde(default)]
+    pub nodes: Vec<usize>,
+    /// Number of additional firebreak nodes chosen by `strategy`
+    #[serde(default)]
+    pub budget: usize,
+    #[serde(default)]
+    pub strategy: OSMFFirebreakStrategy,
+}
+
+/// Objective that strategies try to maximize
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OSMFObjective {
+    /// Maximize the number of nodes that do not burn
+    #[default]
+    SavedNodes,
+    /// Maximize the total weight of the nodes that do not burn
+    SavedWeight,
+}
+
+/// Determines whether firefighters defend nodes or protect edges
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OSMFDefenseMode {
+    /// Firefighters defend nodes
+    #[default]
+    Nodes,
+    /// Firefighters block edges. The fire cannot cross a blocked edge in any direction.
+    BlockEdges,
+    /// Firefighters orient edges. The fire can cross an oriented edge only in the
+    /// opposite direction of the one it was protected against.
+    OrientEdges,
+}
+
+/// Deployment of `num_ffs` firefighters at time `time`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFDeployment {
+    pub time: TimeUnit,
+    pub num_ffs: usize,
+}
+
+/// Schedule that determines how many firefighters are deployed at which time
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(tag = "type")]
+pub enum OSMFFirefighterSchedule {
+    /// `num_ffs` firefighters are deployed every `strategy_every` time units
+    #[default]
+    Constant,
+    /// Firefighters are deployed at the times of the deployments, which are ordered by time
+    Explicit { deployments: Vec<OSMFDeployment> },
+    /// Firefighters are deployed every `strategy_every` time units. The first deployment
+    /// consists of `initial` firefighters, and each following deployment consists of
+    /// `increment` more firefighters, up to `max` firefighters.
+    RampUp { initial: usize, increment: usize, max: usize },
+}
+
+/// Conditions that end a simulation before the fire stops spreading
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OSMFTermination {
+    /// Time after which the simulation ends
+    #[serde(default)]
+    pub max_time: Option<TimeUnit>,
+    /// Wall-clock budget of the simulation in milliseconds
+    #[serde(default)]
+    pub max_millis: Option<u64>,
+    /// Number of strategy rounds after which the simulation ends
+    #[serde(default)]
+    pub max_strategy_rounds: Option<usize>,
+    /// End the simulation as soon as the fire is contained, i.e. cannot spread anymore, even if
+    /// scheduled secondary ignitions are still pending
+    #[serde(default)]
+    pub stop_when_contained: bool,
+}
+
+/// Reason why a simulation ended
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OSMFEndReason {
+    /// The fire cannot spread anymore and no secondary ignitions are pending
+    FireStopped,
+    /// The fire cannot spread anymore, but scheduled secondary ignitions are still pending
+    Contained,
+    /// The maximum time was reached
+    MaxTime,
+    /// The wall-clock budget was exhausted
+    MaxMillis,
+    /// The maximum number of strategy rounds was reached
+    MaxStrategyRounds,
+}
+
+/// Nodes defended at time `time` by an external defense plan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFPlannedDefense {
+    pub time: TimeUnit,
+    pub nodes: Vec<usize>,
+}
+
+/// Defense plan made outside of this project, e.g. by an ILP solver or by hand
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OSMFDefensePlan {
+    pub roots: Vec<usize>,
+    pub defenses: Vec<OSMFPlannedDefense>,
+}
+
+/// Violation of the rules of the firefighter problem by an external defense plan
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum OSMFPlanViolation {
+    /// The node does not exist in the graph
+    UnknownNode { node_id: usize },
+    /// The fire root is a firebreak
+    DefendedRoot { node_id: usize },
+    /// Nodes can only be defended after time `0`
+    InvalidTime { node_id: usize, time: TimeUnit },
+    /// More nodes were defended by time `time` than firefighters were deployed
+    BudgetExceeded { time: TimeUnit, defended: usize, budget: usize },
+    /// The node was defended at time `time`, but was already burning since `burning_since`
+    NodeBurning { node_id: usize, time: TimeUnit, burning_since: TimeUnit },
+    /// The node was defended at time `time`, but was already defended since `defended_since`
+    NodeDefended { node_id: usize, time: TimeUnit, defended_since: TimeUnit },
+}
 
 /// Settings for a firefighter problem instance
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct OSMFSettings {
     pub graph_name: String,
     pub strategy_name: String,
     pub num_roots: usize,
+    /// Number of firefighters per deployment in the constant firefighter schedule.
+    /// In the mobility model, this is also the number of firefighters that move around.
     pub num_ffs: usize,
     pub strategy_every: TimeUnit,
+    #[serde(default)]
+    pub ff_schedule: OSMFFirefighterSchedule,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub spread_model: OSMFSpreadModel,
+    /// Wind schedule ordered by time. An empty schedule means no wind.
+    #[serde(default)]
+    pub wind_schedule: Vec<OSMFWind>,
+    /// Optional mobility model. Without a mobility model, firefighters can defend any node
+    /// instantly.
+    #[serde(default)]
+    pub mobility: Option<OSMFMobility>,
+    #[serde(default)]
+    pub defense_mode: OSMFDefenseMode,
+    /// Optional time after which burning nodes burn out, i.e. recover, and stop spreading
+    /// the fire. Without a burn duration, nodes burn forever.
+    #[serde(default)]
+    pub burn_duration: Option<TimeUnit>,
+    #[serde(default)]
+    pub ignitions: OSMFIgnitions,
+    #[serde(default)]
+    pub objective: OSMFObjective,
+    #[serde(default)]
+    pub targets: OSMFTargets,
+    #[serde(default)]
+    pub firebreaks: OSMFFirebreaks,
+    #[serde(default)]
+    pub termination: OSMFTermination,
+    /// Values of the tunable parameters of the strategy by parameter name.
+    /// Parameters without a value have their default value.
+    #[serde(default)]
+    pub strategy_params: BTreeMap<String, OSMFParameterValue>,
 }
 
 #[derive(Debug, Display, Error)]
 pub enum OSMFSettingsError {
     #[display(fmt = "Number of fire roots must not be greater than {}: {}", num_nodes, num_roots)]
     InvalidNumRoots { num_nodes: usize, num_roots: usize },
+    #[display(fmt = "Ignition probability must be in [0, 1]: {}", probability)]
+    InvalidIgnitionProbability { probability: f64 },
+    #[display(fmt = "Invalid spread delay distribution: {}", reason)]
+    InvalidDelayDistribution { reason: String },
+    #[display(fmt = "Invalid wind schedule: {}", reason)]
+    InvalidWindSchedule { reason: String },
+    #[display(fmt = "Invalid mobility model: {}", reason)]
+    InvalidMobility { reason: String },
+    #[display(fmt = "Invalid secondary ignitions: {}", reason)]
+    InvalidIgnitions { reason: String },
+    #[display(fmt = "Invalid targets: {}", reason)]
+    InvalidTargets { reason: String },
+    #[display(fmt = "Invalid firebreaks: {}", reason)]
+    InvalidFirebreaks { reason: String },
+    #[display(fmt = "Invalid firefighter schedule: {}", reason)]
+    InvalidFirefighterSchedule { reason: String },
+    #[display(fmt = "Burn duration must be greater than 0")]
+    InvalidBurnDuration,
+    #[display(fmt = "Strategy {} does not support defense mode {:?}", strategy_name, defense_mode)]
+    IncompatibleStrategy { strategy_name: String, defense_mode: OSMFDefenseMode },
+    #[display(fmt = "Number of runs must be greater than 0: {}", num_runs)]
+    InvalidNumRuns { num_runs: usize },
+    #[display(fmt = "Unknown strategy: {}", strategy_name)]
+    UnknownStrategy { strategy_name: String },
+    #[display(fmt = "A strategy named {} already exists", strategy_name)]
+    DuplicateStrategy { strategy_name: String },
+    #[display(fmt = "Invalid parameter of strategy {}: {}", strategy_name, reason)]
+    InvalidStrategyParameter { strategy_name: String, reason: String },
+    #[display(fmt = "Invalid defense plan: {}", reason)]
+    InvalidDefensePlan { reason: String },
+}
+
+impl OSMFSettings {
+    /// Validate these settings against the graph `graph`
+    fn validate(&self, graph: &Graph) -> Result<(), OSMFSettingsError> {
+        if self.num_roots > graph.num_nodes {
+            return Err(OSMFSettingsError::InvalidNumRoots {
+                num_nodes: graph.num_nodes,
+                num_roots: self.num_roots,
+            });
+        }
+
+        if let OSMFSpreadModel::Probabilistic { ignition_probability, ref delay } = self.spread_model {
+            if !(0.0..=1.0).contains(&ignition_probability) {
+                return Err(OSMFSettingsError::InvalidIgnitionProbability {
+                    probability: ignition_probability,
+                });
+            }
+            match *delay {
+                Some(OSMFDelayDistribution::Uniform { min, max }) if min > max => {
+                    return Err(OSMFSettingsError::InvalidDelayDistribution {
+                        reason: format!("min must not be greater than max: {} > {}", min, max),
+                    });
+                }
+                Some(OSMFDelayDistribution::Geometric { mean })
+                    if !(0.0..=MAX_GEOMETRIC_DELAY_MEAN).contains(&mean) => {
+                    return Err(OSMFSettingsError::InvalidDelayDistribution {
+                        reason: format!("mean must be in [0, {}]: {}", MAX_GEOMETRIC_DELAY_MEAN,
+                                        mean),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        for wind in &self.wind_schedule {
+            if !wind.direction.is_finite() || !(0.0..=MAX_WIND_STRENGTH).contains(&wind.strength) {
+                return Err(OSMFSettingsError::InvalidWindSchedule {
+                    reason: format!("direction must be finite and strength must be in [0, {}]: \
+                    {:?}", MAX_WIND_STRENGTH, wind),
+                });
+            }
+        }
+        if self.wind_schedule.windows(2).any(|w| w[0].time >= w[1].time) {
+            return Err(OSMFSettingsError::InvalidWindSchedule {
+                reason: "entries must be strictly ordered by time".to_string(),
+            });
+        }
+
+        match self.ff_schedule {
+            OSMFFirefighterSchedule::Explicit { ref deployments }
+            if deployments.first().is_some_and(|d| d.time == 0)
+                || deployments.windows(2).any(|w| w[0].time >= w[1].time) => {
+                return Err(OSMFSettingsError::InvalidFirefighterSchedule {
+                    reason: "deployments must happen after time 0 and be strictly ordered by time"
+                        .to_string(),
+                });
+            }
+            OSMFFirefighterSchedule::Explicit { .. } => (),
+            _ if self.strategy_every == 0 => {
+                return Err(OSMFSettingsError::InvalidFirefighterSchedule {
+                    reason: "strategy_every must be greater than 0".to_string(),
+                });
+            }
+            OSMFFirefighterSchedule::RampUp { initial, max, .. } if initial > max => {
+                return Err(OSMFSettingsError::InvalidFirefighterSchedule {
+                    reason: format!("initial must not be greater than max: {} > {}", initial, max),
+                });
+            }
+            _ => (),
+        }
+
+        if let Some(ignition) = self.ignitions.scheduled.iter()
+            .find(|ig| ig.time == 0 || ig.node_id >= graph.num_nodes) {
+            return Err(OSMFSettingsError::InvalidIgnitions {
+                reason: format!("scheduled ignitions must happen after time 0 at known nodes: {:?}",
+                                ignition),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.ignitions.random_probability) {
+            return Err(OSMFSettingsError::InvalidIgnitions {
+                reason: format!("random ignition probability must be in [0, 1]: {}",
+                                self.ignitions.random_probability),
+            });
+        }
+
+        if self.burn_duration == Some(0) {
+            return Err(OSMFSettingsError::InvalidBurnDuration);
+        }
+
+        if let Some(target) = self.targets.nodes.iter().find(|&&t| t >= graph.num_nodes) {
+            return Err(OSMFSettingsError::InvalidTargets {
+                reason: format!("unknown target node: {}", target),
+            });
+        }
+        if let Some(node_id) = self.firebreaks.nodes.iter().find(|&&n| n >= graph.num_nodes) {
+            return Err(OSMFSettingsError::InvalidFirebreaks {
+                reason: format!("unknown firebreak node: {}", node_id),
+            });
+        }
+        let num_firebreaks = self.firebreaks.nodes.iter().collect::<BTreeSet<_>>().len()
+            + self.firebreaks.budget;
+        if num_firebreaks + self.num_roots > graph.num_nodes {
+            return Err(OSMFSettingsError::InvalidFirebreaks {
+                reason: format!("{} firebreaks and {} fire roots exceed the number of nodes {}",
+                                num_firebreaks, self.num_roots, graph.num_nodes),
+            });
+        }
+
+        if let Some(ref region) = self.targets.region {
+            if !(region.min_lat <= region.max_lat && region.min_lon <= region.max_lon) {
+                return Err(OSMFSettingsError::InvalidTargets {
+                    reason: format!("region bounds must be ordered: {:?}", region),
+                });
+            }
+        }
+
+        if let Some(ref mobility) = self.mobility {
+            if mobility.stations.is_empty() {
+                return Err(OSMFSettingsError::InvalidMobility {
+                    reason: "at least one station is required".to_string(),
+                });
+            }
+            if let Some(station) = mobility.stations.iter().find(|&&s| s >= graph.num_nodes) {
+                return Err(OSMFSettingsError::InvalidMobility {
+                    reason: format!("unknown station node: {}", station),
+                });
+            }
+            if !mobility.speed.is_finite() || mobility.speed <= 0.0 {
+                return Err(OSMFSettingsError::InvalidMobility {
+                    reason: format!("speed must be finite and positive: {}", mobility.speed),
+                });
+            }
+            if self.defense_mode != OSMFDefenseMode::Nodes {
+                return Err(OSMFSettingsError::InvalidMobility {
+                    reason: "firefighters can only move to nodes they defend".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the ids of all target nodes in `graph` in ascending order, i.e. the explicitly
+    /// given target nodes and all nodes in the target region
+    pub fn target_nodes(&self, graph: &Graph) -> Vec<usize> {
+        let mut targets: BTreeSet<_> = self.targets.nodes.iter().copied().collect();
+        if let Some(ref region) = self.targets.region {
+            targets.extend(graph.nodes().iter()
+                .filter(|&node| region.contains(node))
+                .map(|node| node.id));
+        }
+        targets.into_iter().collect()
+    }
+
+    /// Get the number of firefighters that are deployed at time `time`
+    pub fn firefighters_at(&self, time: TimeUnit) -> usize {
+        match self.ff_schedule {
+            OSMFFirefighterSchedule::Explicit { ref deployments } => deployments.iter()
+                .find(|d| d.time == time)
+                .map_or(0, |d| d.num_ffs),
+            _ if time == 0 || !time.is_multiple_of(self.strategy_every) => 0,
+            OSMFFirefighterSchedule::Constant => self.num_ffs,
+            OSMFFirefighterSchedule::RampUp { initial, increment, max } => {
+                let round = (time / self.strategy_every - 1) as usize;
+                initial.saturating_add(round.saturating_mul(increment)).min(max)
+            }
+        }
+    }
+
+    /// Get the total number of firefighters that are deployed by time `time`, i.e. the number
+    /// of nodes that can be defended by time `time`
+    pub fn firefighters_by(&self, time: TimeUnit) -> usize {
+        match self.ff_schedule {
+            OSMFFirefighterSchedule::Explicit { ref deployments } => deployments.iter()
+                .take_while(|d| d.time <= time)
+                .map(|d| d.num_ffs)
+                .sum(),
+            OSMFFirefighterSchedule::Constant => (time / self.strategy_every) as usize * self.num_ffs,
+            OSMFFirefighterSchedule::RampUp { initial, increment, max } => {
+                let rounds = (time / self.strategy_every) as usize;
+                // Number of deployments before the maximum is reached
+                let ramp_rounds = match (max - initial, increment) {
+                    (0, _) => 0,
+                    (_, 0) => rounds,
+                    (diff, inc) => rounds.min(diff.div_ceil(inc)),
+                };
+                let ramp_sum = ramp_rounds * initial
+                    + increment * (ramp_rounds * ramp_rounds.saturating_sub(1) / 2);
+                ramp_sum + (rounds - ramp_rounds) * max
+            }
+        }
+    }
+
+    /// Get the time of the `rounds`-th strategy round after time `time`.
+    /// For an explicit schedule, only deployments of at least one firefighter count as rounds,
+    /// and the time of the last such deployment is returned if fewer rounds are left.
+    pub fn round_after(&self, time: TimeUnit, rounds: usize) -> TimeUnit {
+        match self.ff_schedule {
+            OSMFFirefighterSchedule::Explicit { ref deployments } => deployments.iter()
+                .filter(|d| d.time > time && d.num_ffs > 0)
+                .take(rounds)
+                .last()
+                .map_or(time, |d| d.time),
+            _ => time.saturating_add((rounds as TimeUnit).saturating_mul(self.strategy_every)),
+        }
+    }
+
+    /// Get the wind that is active at time `time` or `None` if there is no wind
+    pub fn wind_at(&self, time: TimeUnit) -> Option<&OSMFWind> {
+        self.wind_schedule.iter()
+            .take_while(|wind| wind.time <= time)
+            .last()
+    }
 }
 
 /// Node data related to the firefighter problem
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(super) struct NodeData {
     pub node_id: usize,
     time: TimeUnit,
 }
 
-/// Storage for node data
+/// Edge data related to the firefighter problem
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct EdgeData {
+    pub src: usize,
+    pub tgt: usize,
+    time: TimeUnit,
+}
+
+/// A firefighter that moves along the graph in the mobility model
+#[derive(Debug, Serialize)]
+pub(super) struct Firefighter {
+    pub id: usize,
+    pub station: usize,
+    /// Nodes visited by the firefighter and the times at which they were reached.
+    /// The first entry is the station at time `0`.
+    route: Vec<NodeData>,
+    /// Node the firefighter is heading to in order to defend it
+    target: Option<usize>,
+}
+
+impl Firefighter {
+    /// Create a new firefighter located at `station`
+    fn new(id: usize, station: usize) -> Self {
+        Self {
+            id,
+            station,
+            route: vec![NodeData { node_id: station, time: 0 }],
+            target: None,
+        }
+    }
+
+    /// Get the id of the last node on the route of this firefighter
+    pub fn position(&self) -> usize {
+        self.route.last().unwrap().node_id
+    }
+
+    /// Get the time at which this firefighter reaches the last node on its route
+    pub fn arrival_time(&self) -> TimeUnit {
+        self.route.last().unwrap().time
+    }
+
+    /// Is this firefighter neither heading to a node nor travelling?
+    pub fn is_idle(&self) -> bool {
+        self.target.is_none()
+    }
+
+    /// Get the position of this firefighter at time `time` as a tuple of the last node it
+    /// reached, the next node on its route and its progress on the edge between the two nodes
+    pub fn position_at(&self, time: &TimeUnit) -> (usize, usize, f64) {
+        let next = self.route.partition_point(|nd| nd.time <= *time);
+        let last = &self.route[next.max(1) - 1];
+        match self.route.get(next) {
+            Some(next) if next.time > last.time && next.node_id != last.node_id => {
+                let progress = (*time - last.time) as f64 / (next.time - last.time) as f64;
+                (last.node_id, next.node_id, progress)
+            }
+            _ => (last.node_id, last.node_id, 0.0)
+        }
+    }
+
+    /// Send this firefighter along `route` to `target` at time `time`.
+    /// The firefighter waits at its position until it is dispatched.
+    fn dispatch(&mut self, target: usize, route: Vec<NodeData>, time: TimeUnit) {
+        self.target = Some(target);
+        if time > self.arrival_time() {
+            self.route.push(NodeData { node_id: self.position(), time });
+        }
+        self.route.extend(route);
+    }
+}
+
+/// Index of the nodes that caught fire or were defended at each time from `0` to the end time
+/// of a simulation, which is built once after the simulation.
+/// Only the times at which nodes caught fire or were defended are stored, so that the size of
+/// the index does not depend on the end time.
+#[derive(Debug)]
+struct TimeIndex {
+    /// Times at which nodes caught fire or were defended, in increasing order
+    times: Vec<TimeUnit>,
+    burning_at: Vec<Vec<usize>>,
+    defended_at: Vec<Vec<usize>>,
+    burning_by: Vec<usize>,
+    defended_by: Vec<usize>,
+}
+
+impl TimeIndex {
+    /// Build the time index of the nodes in `burning` and `defended` up to time `end_time`
+    fn new(burning: &LayeredMap<usize, NodeData>, defended: &LayeredMap<usize, NodeData>,
+           end_time: TimeUnit) -> Self {
+        let times: Vec<_> = burning.values().chain(defended.values())
+            .map(|nd| nd.time)
+            .filter(|&time| time <= end_time)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index_at = |node_data: &LayeredMap<usize, NodeData>| {
+            let mut nodes_at = vec![Vec::new(); times.len()];
+            for nd in node_data.values() {
+                if let Ok(pos) = times.binary_search(&nd.time) {
+                    nodes_at[pos].push(nd.node_id);
+                }
+            }
+            nodes_at
+        };
+        let prefix_sums = |nodes_at: &[Vec<usize>]| nodes_at.iter()
+            .scan(0, |total, nodes| {
+                *total += nodes.len();
+                Some(*total)
+            })
+            .collect::<Vec<_>>();
+
+        let burning_at = index_at(burning);
+        let defended_at = index_at(defended);
+        Self {
+            burning_by: prefix_sums(&burning_at),
+            defended_by: prefix_sums(&defended_at),
+            times,
+            burning_at,
+            defended_at,
+        }
+    }
+
+    /// Get the index position of the last time at or before time `time`, if any
+    fn position_by(&self, time: &TimeUnit) -> Option<usize> {
+        self.times.partition_point(|t| t <= time).checked_sub(1)
+    }
+
+    /// Count all nodes burning by time `time`
+    fn burning_by(&self, time: &TimeUnit) -> usize {
+        self.position_by(time).map_or(0, |pos| self.burning_by[pos])
+    }
+
+    /// Count all nodes defended by time `time`
+    fn defended_by(&self, time: &TimeUnit) -> usize {
+        self.position_by(time).map_or(0, |pos| self.defended_by[pos])
+    }
+
+    /// Get the nodes that caught fire at time `time`
+    fn burning_at(&self, time: &TimeUnit) -> &[usize] {
+        self.times.binary_search(time).map_or(&[], |pos| &self.burning_at[pos])
+    }
+
+    /// Get the nodes that were defended at time `time`
+    fn defended_at(&self, time: &TimeUnit) -> &[usize] {
+        self.times.binary_search(time).map_or(&[], |pos| &self.defended_at[pos])
+    }
+}
+
+/// Storage for node data.
+/// Forks share the node data with the storage they were created from and only store the
+/// changes made to them, see [`LayeredMap`].
 #[derive(Debug, Serialize)]
 pub(super) struct NodeDataStorage {
-    burning: BTreeMap<usize, NodeData>,
-    defended: BTreeMap<usize, NodeData>,
+    burning: LayeredMap<usize, NodeData>,
+    defended: LayeredMap<usize, NodeData>,
+    roots: Arc<BTreeSet<usize>>,
+    late_ignitions: Arc<BTreeSet<usize>>,
+    firebreaks: Arc<BTreeSet<usize>>,
+    burn_duration: Option<TimeUnit>,
+    defense_mode: OSMFDefenseMode,
+    protected: LayeredMap<(usize, usize), EdgeData>,
+    firefighters: Vec<Firefighter>,
+    defense_orders: Vec<usize>,
+    #[serde(skip)]
+    time_index: Option<TimeIndex>,
+    /// Do not log changes, e.g. when looking ahead
+    #[serde(skip)]
+    silent: bool,
 }
 
 impl NodeDataStorage {
     /// Create a new node data storage
-    fn new() -> Self {
+    pub(super) fn new(defense_mode: OSMFDefenseMode, burn_duration: Option<TimeUnit>) -> Self {
         Self {
-            burning: BTreeMap::new(),
-            defended: BTreeMap::new(),
+            burning: LayeredMap::new(),
+            defended: LayeredMap::new(),
+            roots: Arc::default(),
+            late_ignitions: Arc::default(),
+            firebreaks: Arc::default(),
+            burn_duration,
+            defense_mode,
+            protected: LayeredMap::new(),
+            firefighters: Vec::new(),
+            defense_orders: Vec::new(),
+            time_index: None,
+            silent: false,
         }
     }
 
-    /// Is node with id `node_id` a fire root?
-    pub fn is_root(&self, node_id: &usize) -> bool {
-        match self.burning.get(node_id) {
-            Some(nd) => nd.time == 0,
-            None => false
+    /// Create a silent copy of the fire and the defense in this storage to look ahead.
+    /// Firefighters of the mobility model and their orders are not copied, such that nodes
+    /// are defended instantly in the copy.
+    pub(super) fn fork(&self) -> Self {
+        Self {
+            burning: self.burning.fork(),
+            defended: self.defended.fork(),
+            roots: self.roots.clone(),
+            late_ignitions: self.late_ignitions.clone(),
+            firebreaks: self.firebreaks.clone(),
+            burn_duration: self.burn_duration,
+            defense_mode: self.defense_mode,
+            protected: self.protected.fork(),
+            firefighters: Vec::new(),
+            defense_orders: Vec::new(),
+            time_index: None,
+            silent: true,
         }
     }
 
+    /// Station `num_ffs` firefighters at `stations` in a round robin manner.
+    /// Afterwards, nodes can only be defended by firefighters that reached them.
+    fn station_firefighters(&mut self, stations: &[usize], num_ffs: usize) {
+        self.firefighters = (0..num_ffs)
+            .map(|id| Firefighter::new(id, stations[id % stations.len()]))
+            .collect();
+    }
+
+    /// Returns true if firefighters have to move to the nodes they defend
+    pub fn is_mobile(&self) -> bool {
+        !self.firefighters.is_empty()
+    }
+
+    /// Get all firefighters of the mobility model
+    pub fn firefighters(&self) -> &[Firefighter] {
+        &self.firefighters
+    }
+
+    /// Is some firefighter heading to the node with id `node_id`?
+    pub fn is_targeted(&self, node_id: &usize) -> bool {
+        self.firefighters.iter().any(|ff| ff.target == Some(*node_id))
+    }
+
+    /// Is node with id `node_id` a fire root, i.e. was it ignited at time `0`?
+    pub fn is_root(&self, node_id: &usize) -> bool {
+        self.roots.contains(node_id)
+    }
+
+    /// Is node with id `node_id` a firebreak, i.e. was it defended at time `0`?
+    pub fn is_firebreak(&self, node_id: &usize) -> bool {
+        self.firebreaks.contains(node_id)
+    }
+
+    /// Count all firebreaks
+    pub fn count_firebreaks(&self) -> usize {
+        self.firebreaks.len()
+    }
+
+    /// Was node with id `node_id` ignited by a secondary ignition after time `0`?
+    pub fn is_late_ignition(&self, node_id: &usize) -> bool {
+        self.late_ignitions.contains(node_id)
+    }
+
     /// Is node with id `node_id` burning?
     pub fn is_burning(&self, node_id: &usize) -> bool {
         self.burning.contains_key(node_id)
@@ -73,13 +849,41 @@ impl NodeDataStorage {
         }
     }
 
+    /// Build the time index of all burning and defended nodes up to time `end_time`.
+    /// The storage must not change afterwards.
+    fn build_time_index(&mut self, end_time: TimeUnit) {
+        self.time_index = Some(TimeIndex::new(&self.burning, &self.defended, end_time));
+    }
+
     /// Count all nodes burning by time `time`
     pub fn count_burning_by(&self, time: &TimeUnit) -> usize {
+        if let Some(ref index) = self.time_index {
+            return index.burning_by(time);
+        }
         self.burning.values()
             .filter(|nd| nd.time <= *time)
             .count()
     }
 
+    /// Is node with id `node_id` burned out by time `time`?
+    /// Nodes only burn out if a burn duration is set.
+    pub fn is_burned_out_by(&self, node_id: &usize, time: &TimeUnit) -> bool {
+        match (self.burning.get(node_id), self.burn_duration) {
+            (Some(nd), Some(burn_duration)) => nd.time + burn_duration <= *time,
+            _ => false
+        }
+    }
+
+    /// Count all nodes burned out by time `time`
+    pub fn count_burned_out_by(&self, time: &TimeUnit) -> usize {
+        match self.burn_duration {
+            Some(burn_duration) => self.burning.values()
+                .filter(|nd| nd.time + burn_duration <= *time)
+                .count(),
+            None => 0
+        }
+    }
+
     /// Is node with id `node_id` defended?
     pub fn is_defended(&self, node_id: &usize) -> bool {
         self.defended.contains_key(node_id)
@@ -95,6 +899,9 @@ impl NodeDataStorage {
 
     /// Count all nodes defended by time `time`
     pub fn count_defended_by(&self, time: &TimeUnit) -> usize {
+        if let Some(ref index) = self.time_index {
+            return index.defended_by(time);
+        }
         self.defended.values()
             .filter(|nd| nd.time <= *time)
             .count()
@@ -107,7 +914,7 @@ impl NodeDataStorage {
 
     /// Mark all nodes in `nodes` as burning at time `time`
     fn mark_burning(&mut self, nodes: &Vec<usize>, time: TimeUnit) {
-        if !nodes.is_empty() {
+        if !nodes.is_empty() && !self.silent {
             log::debug!("Burning nodes {:?} in round {}", nodes, time);
         }
         for node_id in nodes {
@@ -118,9 +925,27 @@ impl NodeDataStorage {
         }
     }
 
-    /// Mark all nodes in `nodes` as defended at time `time`
+    /// Mark all nodes in `nodes` as defended at time `time`.
+    /// Nodes defended at time `0` are firebreaks that are placed before the fire starts.
+    /// Otherwise, in the mobility model, the nodes are ordered to be defended instead and are
+    /// defended as soon as a firefighter reaches them.
     pub fn mark_defended(&mut self, nodes: &[usize], time: TimeUnit) {
-        if !nodes.is_empty() {
+        if time == 0 {
+            Arc::make_mut(&mut self.firebreaks).extend(nodes);
+            self.defend(nodes, time);
+        } else if self.is_mobile() {
+            if !nodes.is_empty() {
+                log::debug!("Ordering defense of nodes {:?} in round {}", nodes, time);
+            }
+            self.defense_orders.extend_from_slice(nodes);
+        } else {
+            self.defend(nodes, time);
+        }
+    }
+
+    /// Defend all nodes in `nodes` at time `time`
+    fn defend(&mut self, nodes: &[usize], time: TimeUnit) {
+        if !nodes.is_empty() && !self.silent {
             log::debug!("Defending nodes {:?} in round {}", nodes, time);
         }
         for node_id in nodes {
@@ -131,9 +956,63 @@ impl NodeDataStorage {
         }
     }
 
-    /// Get the node data of all burning vertices
-    fn get_burning_node_data(&self) -> Vec<&NodeData> {
-        self.burning.values().collect()
+    /// Take all pending defense orders
+    fn take_defense_orders(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.defense_orders)
+    }
+
+    /// Let all firefighters that reached their target by time `time` defend it, if it is
+    /// still undefended
+    fn advance_firefighters(&mut self, time: TimeUnit) {
+        let reached: Vec<_> = self.firefighters.iter_mut()
+            .filter(|ff| ff.target.is_some() && ff.arrival_time() <= time)
+            .filter_map(|ff| ff.target.take())
+            .collect();
+        let to_defend: Vec<_> = reached.into_iter()
+            .filter(|node_id| self.is_undefended(node_id))
+            .collect();
+        self.defend(&to_defend, time);
+    }
+
+    /// Protect all edges in `edges` at time `time`, so that the fire cannot cross them
+    /// from source to target anymore.
+    /// Depending on the defense mode, the edges are blocked in both directions or oriented,
+    /// i.e. the fire can still cross them from target to source.
+    pub fn mark_protected(&mut self, edges: &[(usize, usize)], time: TimeUnit) {
+        if !edges.is_empty() && !self.silent {
+            log::debug!("Protecting edges {:?} in round {}", edges, time);
+        }
+        for &(src, tgt) in edges {
+            self.protected.insert((src, tgt), EdgeData {
+                src,
+                tgt,
+                time,
+            });
+        }
+    }
+
+    /// Is the fire prevented from crossing the edge from `src` to `tgt`?
+    pub fn is_blocked(&self, src: &usize, tgt: &usize) -> bool {
+        self.protected.contains_key(&(*src, *tgt))
+            || (self.defense_mode == OSMFDefenseMode::BlockEdges
+            && self.protected.contains_key(&(*tgt, *src)))
+    }
+
+    /// Is the fire prevented from crossing the edge from `src` to `tgt` by time `time`?
+    pub fn is_blocked_by(&self, src: &usize, tgt: &usize, time: &TimeUnit) -> bool {
+        let protected_by = |key: &(usize, usize)| match self.protected.get(key) {
+            Some(ed) => ed.time <= *time,
+            None => false
+        };
+        protected_by(&(*src, *tgt))
+            || (self.defense_mode == OSMFDefenseMode::BlockEdges && protected_by(&(*tgt, *src)))
+    }
+
+    /// Count all edges protected by time `time`
+    pub fn count_protected_by(&self, time: &TimeUnit) -> usize {
+        self.protected.values()
+            .filter(|ed| ed.time <= *time)
+            .count()
     }
 
     /// Get the id's of all burning vertices
@@ -141,21 +1020,53 @@ impl NodeDataStorage {
         self.burning.keys().map(usize::to_owned).collect()
     }
 
+    /// Get the id's of all defended vertices, including firebreaks
+    pub fn get_defended(&self) -> Vec<usize> {
+        self.defended.keys().map(usize::to_owned).collect()
+    }
+
     /// Get the id's of all burning vertices at time `time`
     pub fn get_burning_at(&self, time: &TimeUnit) -> Vec<usize> {
+        if let Some(ref index) = self.time_index {
+            return index.burning_at(time).to_vec();
+        }
         self.burning.values()
             .filter(|&nd| nd.time == *time)
             .map(|nd| nd.node_id)
             .collect::<Vec<_>>()
     }
 
-    /// Get the id's of all fire roots, i.e., all burning vertices at time `0`
+    /// Get the id's of all fire roots, i.e., all vertices ignited at time `0`
     pub fn get_roots(&self) -> Vec<usize> {
-        self.get_burning_at(&0)
+        self.roots.iter().copied().collect()
+    }
+
+    /// Get the time at which the node with id `node_id` caught fire
+    pub fn get_burning_time(&self, node_id: &usize) -> Option<TimeUnit> {
+        self.burning.get(node_id).map(|nd| nd.time)
+    }
+
+    /// Get the time at which the node with id `node_id` was defended
+    pub fn get_defense_time(&self, node_id: &usize) -> Option<TimeUnit> {
+        self.defended.get(node_id).map(|nd| nd.time)
+    }
+
+    /// Ignite all nodes in `nodes` at time `time`.
+    /// Nodes ignited at time `0` are fire roots, nodes ignited later are secondary ignitions.
+    fn mark_ignited(&mut self, nodes: &Vec<usize>, time: TimeUnit) {
+        self.mark_burning(nodes, time);
+        if time == 0 {
+            Arc::make_mut(&mut self.roots).extend(nodes);
+        } else {
+            Arc::make_mut(&mut self.late_ignitions).extend(nodes);
+        }
     }
 
     /// Get the id's of all defended vertices at time `time`
     pub fn get_defended_at(&self, time: &TimeUnit) -> Vec<usize> {
+        if let Some(ref index) = self.time_index {
+            return index.defended_at(time).to_vec();
+        }
         self.defended.values()
             .filter(|&nd| nd.time == *time)
             .map(|nd| nd.node_id)
@@ -163,60 +1074,364 @@ impl NodeDataStorage {
     }
 }
 
+/// External defense plan without nodes that do not exist in the graph
+#[derive(Debug)]
+struct PlanReplay {
+    roots: Vec<usize>,
+    /// Planned defenses grouped by time
+    defenses: BTreeMap<TimeUnit, Vec<usize>>,
+}
+
 /// Container for data about the simulation of a firefighter problem instance
 #[derive(Serialize)]
 pub struct OSMFSimulationResponse<'a> {
     pub nodes_burned: usize,
+    /// Nodes defended by firefighters, excluding firebreaks
     pub nodes_defended: usize,
+    pub firebreaks: usize,
+    pub edges_protected: usize,
     nodes_total: usize,
+    pub weight_burned: f64,
+    pub weight_defended: f64,
+    pub weight_saved: f64,
+    weight_total: f64,
+    pub targets_burned: usize,
+    pub targets: Vec<OSMFTargetStatus>,
+    /// Time from which on no undefended neighbor of the fire remained, or `None` if the fire
+    /// was not contained at the end of the simulation.
+    /// Secondary ignitions can restart a contained fire, so this is the start of the final
+    /// period in which the fire front was empty.
+    pub containment_time: Option<TimeUnit>,
+    /// Number of undefended neighbors of the fire at each time from `0` to `end_time`
+    pub fire_front: &'a [usize],
+    /// Total length of the roads between burned nodes
+    pub burned_road_length: usize,
+    /// Number of connected components formed by the nodes that did not burn
+    pub saved_components: usize,
+    /// Number of saved nodes per defended node, including firebreaks
+    pub ff_efficiency: Option<f64>,
     pub end_time: TimeUnit,
+    pub end_reason: Option<OSMFEndReason>,
     pub simulation_time_millis: u128,
+    pub seed: u64,
     view_bounds: &'a GridBounds,
     view_center: Coords,
 }
 
+/// Whether and when a target node burned
+#[derive(Serialize)]
+pub struct OSMFTargetStatus {
+    pub node_id: usize,
+    pub burned_at: Option<TimeUnit>,
+}
+
+/// Container for the number of burned and defended nodes at each time from `0` to the end time
+/// of a firefighter simulation. Defended nodes include firebreaks.
+#[derive(Serialize)]
+pub struct OSMFTimeSeries {
+    pub end_time: TimeUnit,
+    /// Number of nodes that burned by each time
+    pub burned_cumulative: Vec<usize>,
+    /// Number of nodes that caught fire at each time
+    pub burned_new: Vec<usize>,
+    /// Number of nodes that were defended by each time
+    pub defended_cumulative: Vec<usize>,
+    /// Number of nodes that were defended at each time
+    pub defended_new: Vec<usize>,
+}
+
+/// Container for the result of validating an external defense plan. The simulation results
+/// are only given if the plan does not violate any rules.
+#[derive(Serialize)]
+pub struct OSMFPlanValidation<'a> {
+    pub valid: bool,
+    pub violations: &'a [OSMFPlanViolation],
+    pub results: Option<OSMFSimulationResponse<'a>>,
+}
+
+/// Number of nodes per state at a specific step of a firefighter simulation
+#[derive(Serialize)]
+pub struct OSMFNodeStateCounts {
+    /// Nodes that are neither burning, burned out nor defended
+    susceptible: usize,
+    /// Nodes that are burning and spread the fire
+    burning: usize,
+    /// Nodes that burned and do not spread the fire anymore
+    burned_out: usize,
+    /// Nodes that are defended, i.e. vaccinated, by firefighters
+    defended: usize,
+    /// Nodes that were defended before the fire started
+    firebreak: usize,
+}
+
 /// Container for data about a specific step of a firefighter simulation
 #[derive(Serialize)]
 pub struct OSMFSimulationStepMetadata {
     nodes_burned_by: usize,
     nodes_defended_by: usize,
+    firebreaks: usize,
+    edges_protected_by: usize,
+    fire_front: usize,
+    node_states: OSMFNodeStateCounts,
     nodes_burned_at: Vec<usize>,
     nodes_defended_at: Vec<usize>,
 }
 
+/// Get the time it takes the fire to spread along `edge` if the source of `edge` started
+/// burning at time `ignition_time`, or `None` if the fire never spreads along `edge`.
+/// In the probabilistic spread model, the outcome is drawn once per edge and cached in
+/// `spread_delays`.
+fn spread_delay(graph: &Graph, settings: &OSMFSettings, rng: &mut StdRng,
+                spread_delays: &mut HashMap<(usize, usize), Option<TimeUnit>>, edge: &Edge,
+                ignition_time: TimeUnit) -> Option<TimeUnit> {
+    let delay = match settings.spread_model {
+        OSMFSpreadModel::Deterministic => edge.dist as TimeUnit,
+        OSMFSpreadModel::Probabilistic { ignition_probability, ref delay } => {
+            (*spread_delays.entry((edge.src, edge.tgt))
+                .or_insert_with(|| {
+                    if rng.gen_bool(ignition_probability) {
+                        let extra_delay = delay.as_ref()
+                            .map_or(0, |delay| delay.sample(rng));
+                        Some((edge.dist as TimeUnit).saturating_add(extra_delay))
+                    } else {
+                        None
+                    }
+                }))?
+        }
+    };
+    Some(wind_spread_delay(graph, settings, edge, ignition_time, delay))
+}
+
+/// Get the spread delay of `edge` like `spread_delay`, but without drawing it in the
+/// probabilistic spread model. Returns `None` if the delay has not been drawn yet.
+fn drawn_spread_delay(graph: &Graph, settings: &OSMFSettings,
+                      spread_delays: &HashMap<(usize, usize), Option<TimeUnit>>, edge: &Edge,
+                      ignition_time: TimeUnit) -> Option<Option<TimeUnit>> {
+    let delay = match settings.spread_model {
+        OSMFSpreadModel::Deterministic => edge.dist as TimeUnit,
+        OSMFSpreadModel::Probabilistic { .. } => match *spread_delays.get(&(edge.src, edge.tgt))? {
+            Some(delay) => delay,
+            None => return Some(None),
+        },
+    };
+    Some(Some(wind_spread_delay(graph, settings, edge, ignition_time, delay)))
+}
+
+/// Returns true if the fire spreads along an edge with the spread delay `delay` before the
+/// edge source burns out
+fn spreads_before_burn_out(settings: &OSMFSettings, delay: TimeUnit) -> bool {
+    settings.burn_duration.is_none_or(|burn_duration| delay < burn_duration)
+}
+
+/// Scale the spread delay `delay` of `edge` by the wind that blew when the edge source started
+/// burning at time `ignition_time`
+fn wind_spread_delay(graph: &Graph, settings: &OSMFSettings, edge: &Edge, ignition_time: TimeUnit,
+                     delay: TimeUnit) -> TimeUnit {
+    match settings.wind_at(ignition_time) {
+        Some(wind) => {
+            let bearing = graph.get_node(edge.src).bearing_to(graph.get_node(edge.tgt));
+            (delay as f64 * wind.spread_factor(bearing)).round() as TimeUnit
+        }
+        None => delay
+    }
+}
+
+/// Spread the fire at time `time` to all undefended nodes that are adjacent to burning nodes
+/// once the delay `spread_delay` of the edge in between has passed.
+/// Returns true if there is at least one node that may burn at some point in the future.
+fn spread_fire<F>(graph: &Graph, settings: &OSMFSettings, node_data: &mut NodeDataStorage,
+                  time: TimeUnit, mut spread_delay: F) -> bool
+    where F: FnMut(&Edge, TimeUnit) -> Option<TimeUnit> {
+    let mut to_burn = Vec::new();
+    let mut is_active = false;
+
+    // For all undefended neighbours that are not already burning, check whether they have
+    // to be added to `to_burn`
+    for nd in node_data.burning.values() {
+        for edge in graph.get_outgoing_edges(nd.node_id) {
+            if node_data.is_undefended(&edge.tgt) && !node_data.is_blocked(&edge.src, &edge.tgt) {
+                let delay = match spread_delay(edge, nd.time) {
+                    // Burned out nodes do not spread the fire anymore
+                    Some(delay) if spreads_before_burn_out(settings, delay) => delay,
+                    _ => continue // The fire never spreads along this edge
+                };
+                // There is at least one node to be burned at some point in the future
+                is_active = true;
+                // Burn the node if the time exceeds the time at which the edge source
+                // started burning plus the spread delay of the edge
+                if time >= nd.time.saturating_add(delay) {
+                    to_burn.push(edge.tgt);
+                }
+            }
+        }
+    }
+
+    // Burn all nodes in `to_burn`
+    node_data.mark_burning(&to_burn, time);
+    is_active
+}
+
+/// Engine that advances a silent copy of the state of a simulation step by step without a
+/// view, termination conditions or secondary ignitions, e.g. for strategies to look ahead.
+/// The fire spreads as in the deterministic spread model with wind.
+pub(super) struct StepEngine<'a> {
+    graph: &'a Graph,
+    settings: &'a OSMFSettings,
+    node_data: NodeDataStorage,
+    time: TimeUnit,
+    is_active: bool,
+}
+
+impl<'a> StepEngine<'a> {
+    /// Create a new engine that continues from the state in `node_data` at time `time`,
+    /// before the fire spread at that time
+    pub(super) fn new(graph: &'a Graph, settings: &'a OSMFSettings, node_data: &NodeDataStorage,
+                      time: TimeUnit) -> Self {
+        Self {
+            graph,
+            settings,
+            node_data: node_data.fork(),
+            time,
+            is_active: true,
+        }
+    }
+
+    /// Get the state of the copy
+    pub(super) fn node_data(&self) -> &NodeDataStorage {
+        &self.node_data
+    }
+
+    /// Get the mutable state of the copy
+    pub(super) fn node_data_mut(&mut self) -> &mut NodeDataStorage {
+        &mut self.node_data
+    }
+
+    /// Get the current time of the copy
+    pub(super) fn time(&self) -> TimeUnit {
+        self.time
+    }
+
+    /// Returns true if the fire may still spread
+    pub(super) fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Defend all nodes in `nodes` at the current time
+    pub(super) fn defend(&mut self, nodes: &[usize]) {
+        self.node_data.mark_defended(nodes, self.time);
+    }
+
+    /// Spread the fire at the current time
+    pub(super) fn spread_fire(&mut self) {
+        let (graph, settings) = (self.graph, self.settings);
+        self.is_active = spread_fire(graph, settings, &mut self.node_data, self.time,
+                                     |edge, ignition_time| Some(wind_spread_delay(
+                                         graph, settings, edge, ignition_time,
+                                         edge.dist as TimeUnit)));
+    }
+
+    /// Execute the next time step, in which `policy` contains the fire if firefighters are
+    /// deployed and the fire spreads afterwards
+    pub(super) fn step<F>(&mut self, mut policy: F)
+        where F: FnMut(&OSMFSettings, &mut NodeDataStorage, TimeUnit) {
+        self.time += 1;
+        if self.settings.firefighters_at(self.time) > 0 {
+            policy(self.settings, &mut self.node_data, self.time);
+        }
+        self.spread_fire();
+    }
+
+    /// Get the number or the weight of the burned nodes, depending on the objective
+    pub(super) fn burned_cost(&self) -> f64 {
+        match self.settings.objective {
+            OSMFObjective::SavedNodes => self.node_data.burning.len() as f64,
+            OSMFObjective::SavedWeight => self.node_data.burning.keys()
+                .map(|&node_id| self.graph.get_node_weight(node_id))
+                .sum(),
+        }
+    }
+}
+
 /// A firefighter problem instance
 #[derive(Debug)]
 pub struct OSMFProblem {
     graph: Arc<Graph>,
     settings: OSMFSettings,
-    strategy: OSMFStrategy,
+    /// Fire containment strategy, which is `None` when replaying an external defense plan
+    strategy: Option<OSMFStrategy>,
     node_data: NodeDataStorage,
     global_time: TimeUnit,
     simulation_time_millis: u128,
     is_active: bool,
+    end_reason: Option<OSMFEndReason>,
+    strategy_rounds: usize,
+    fire_front: Vec<usize>,
+    /// Number of burning and defended nodes, protected edges and drawn spread delays when the
+    /// size of the fire front was last computed
+    fire_front_state: (usize, usize, usize, usize),
+    /// External defense plan that replaces the containment strategy
+    plan: Option<PlanReplay>,
+    plan_violations: Vec<OSMFPlanViolation>,
+    seed: u64,
+    rng: StdRng,
+    spread_delays: HashMap<(usize, usize), Option<TimeUnit>>,
     view: View,
 }
 
 impl OSMFProblem {
     /// Create a new firefighter problem instance
     pub fn new(graph: Arc<Graph>, settings: OSMFSettings, strategy: OSMFStrategy) -> Result<Self, OSMFSettingsError> {
-        if settings.num_roots > graph.num_nodes {
-            let err = OSMFSettingsError::InvalidNumRoots {
-                num_nodes: graph.num_nodes,
-                num_roots: settings.num_roots,
-            };
+        Self::with_strategy(graph, settings, Some(strategy))
+    }
+
+    /// Create a new firefighter problem instance that is contained by the strategy `strategy`,
+    /// if any
+    fn with_strategy(graph: Arc<Graph>, mut settings: OSMFSettings, mut strategy: Option<OSMFStrategy>) -> Result<Self, OSMFSettingsError> {
+        if let Err(err) = settings.validate(&graph) {
             log::warn!("{}", err.to_string());
             return Err(err);
         }
 
+        if let Some(ref mut strategy) = strategy {
+            if !strategy.supports(settings.defense_mode) {
+                let err = OSMFSettingsError::IncompatibleStrategy {
+                    strategy_name: settings.strategy_name.clone(),
+                    defense_mode: settings.defense_mode,
+                };
+                log::warn!("{}", err);
+                return Err(err);
+            }
+
+            if let Err(err) = strategy.configure(&settings.strategy_params) {
+                log::warn!("{}", err);
+                return Err(err);
+            }
+        }
+
+        // The drawn seed is kept in the settings, so that strategies derive their randomness
+        // from the same seed as the simulation
+        let seed = *settings.seed.get_or_insert_with(|| thread_rng().gen());
+        let mut node_data = NodeDataStorage::new(settings.defense_mode, settings.burn_duration);
+        if let Some(ref mobility) = settings.mobility {
+            node_data.station_firefighters(&mobility.stations, settings.num_ffs);
+        }
         let problem = Self {
             graph: graph.clone(),
             settings,
             strategy,
-            node_data: NodeDataStorage::new(),
+            node_data,
             global_time: 0,
             simulation_time_millis: 0,
             is_active: true,
+            end_reason: None,
+            strategy_rounds: 0,
+            fire_front: Vec::new(),
+            fire_front_state: (0, 0, 0, 0),
+            plan: None,
+            plan_violations: Vec::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            spread_delays: HashMap::new(),
             view: View::new(graph, 1920, 1080),
         };
         log::info!("Initialized problem configuration. settings={:?}.", &problem.settings);
@@ -224,53 +1439,311 @@ impl OSMFProblem {
         Ok(problem)
     }
 
-    /// Generate `num_roots` fire roots
+    /// Create a new firefighter problem instance that replays the external defense plan `plan`
+    /// instead of running a containment strategy, so the strategy in `settings` is ignored.
+    /// Violations of the rules that do not depend on the spread of the fire are recorded
+    /// immediately, all other violations while simulating.
+    pub fn with_plan(graph: Arc<Graph>, settings: OSMFSettings, plan: OSMFDefensePlan) -> Result<Self, OSMFSettingsError> {
+        if settings.mobility.is_some() || settings.defense_mode != OSMFDefenseMode::Nodes {
+            return Err(OSMFSettingsError::InvalidDefensePlan {
+                reason: "plans can only defend nodes without a mobility model".to_string(),
+            });
+        }
+        let mut problem = Self::with_strategy(graph, settings, None)?;
+
+        let num_nodes = problem.graph.num_nodes;
+        let mut violations = Vec::new();
+        let mut roots = BTreeSet::new();
+        for &node_id in &plan.roots {
+            if node_id >= num_nodes {
+                violations.push(OSMFPlanViolation::UnknownNode { node_id });
+            } else {
+                roots.insert(node_id);
+            }
+        }
+
+        let mut defenses: BTreeMap<TimeUnit, Vec<usize>> = BTreeMap::new();
+        for defense in &plan.defenses {
+            for &node_id in &defense.nodes {
+                if node_id >= num_nodes {
+                    violations.push(OSMFPlanViolation::UnknownNode { node_id });
+                } else if defense.time == 0 {
+                    violations.push(OSMFPlanViolation::InvalidTime { node_id, time: 0 });
+                } else {
+                    defenses.entry(defense.time).or_default().push(node_id);
+                }
+            }
+        }
+
+        let mut defended = 0;
+        for (&time, nodes) in &defenses {
+            defended += nodes.len();
+            let budget = problem.settings.firefighters_by(time);
+            if defended > budget {
+                violations.push(OSMFPlanViolation::BudgetExceeded { time, defended, budget });
+            }
+        }
+
+        problem.plan = Some(PlanReplay {
+            roots: roots.into_iter().collect(),
+            defenses,
+        });
+        problem.plan_violations = violations;
+
+        Ok(problem)
+    }
+
+    /// Ignite the fire roots of the external defense plan that are not firebreaks
+    fn ignite_plan_roots(&mut self) -> Vec<usize> {
+        let plan = self.plan.as_ref().unwrap();
+        let (defended, roots): (Vec<_>, Vec<_>) = plan.roots.iter()
+            .partition(|node_id| self.node_data.is_firebreak(node_id));
+        self.plan_violations.extend(defended.into_iter()
+            .map(|node_id| OSMFPlanViolation::DefendedRoot { node_id }));
+
+        self.node_data.mark_ignited(&roots, self.global_time);
+
+        log::info!("Ignited fire roots of defense plan");
+
+        roots
+    }
+
+    /// Check the nodes that the external defense plan defends at time `time` and defend all
+    /// nodes that are neither burning nor defended if `apply` is set
+    fn check_plan_defenses(&mut self, time: TimeUnit, apply: bool) {
+        let nodes = match self.plan.as_ref().and_then(|plan| plan.defenses.get(&time)) {
+            Some(nodes) => nodes.clone(),
+            None => return,
+        };
+
+        let mut to_defend = Vec::new();
+        for node_id in nodes {
+            if let Some(burning_since) = self.node_data.get_burning_time(&node_id) {
+                self.plan_violations.push(OSMFPlanViolation::NodeBurning { node_id, time, burning_since });
+            } else if let Some(defended_since) = self.node_data.get_defense_time(&node_id) {
+                self.plan_violations.push(OSMFPlanViolation::NodeDefended { node_id, time, defended_since });
+            } else if to_defend.contains(&node_id) {
+                self.plan_violations.push(OSMFPlanViolation::NodeDefended { node_id, time, defended_since: time });
+            } else {
+                to_defend.push(node_id);
+            }
+        }
+        if apply {
+            self.node_data.mark_defended(&to_defend, time);
+            self.strategy_rounds += 1;
+        }
+    }
+
+    /// Place the explicitly given firebreaks and the firebreaks chosen by the static firebreak
+    /// strategy
+    fn place_firebreaks(&mut self) {
+        let firebreaks = &self.settings.firebreaks;
+        if firebreaks.nodes.is_empty() && firebreaks.budget == 0 {
+            return;
+        }
+
+        let mut nodes: Vec<_> = firebreaks.nodes.iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        nodes.extend(strategy::select_firebreaks(&self.graph, &self.settings, &nodes,
+                                                 &mut self.rng));
+
+        self.node_data.mark_defended(&nodes, self.global_time);
+
+        log::info!("Placed {} firebreaks", nodes.len());
+    }
+
+    /// Generate `num_roots` fire roots that are not firebreaks
     fn gen_fire_roots(&mut self) -> Vec<usize> {
-        let mut rng = thread_rng();
+        let node_data = &self.node_data;
         let roots = self.graph.nodes().iter()
             .map(|node| node.id)
-            .choose_multiple(&mut rng, self.settings.num_roots);
+            .filter(|node_id| !node_data.is_firebreak(node_id))
+            .choose_multiple(&mut self.rng, self.settings.num_roots);
 
-        self.node_data.mark_burning(&roots, self.global_time);
+        self.node_data.mark_ignited(&roots, self.global_time);
 
         log::info!("Generated fire roots");
 
         roots
     }
 
+    /// Get the time it takes the fire to spread along `edge` if the source of `edge` started
+    /// burning at time `ignition_time`, or `None` if the fire never spreads along `edge`
+    #[cfg(test)]
+    fn spread_delay(&mut self, edge: &Edge, ignition_time: TimeUnit) -> Option<TimeUnit> {
+        spread_delay(&self.graph, &self.settings, &mut self.rng, &mut self.spread_delays, edge,
+                     ignition_time)
+    }
+
     /// Spread the fire to all nodes that are adjacent to burning nodes.
     /// Defended nodes will remain defended.
     fn spread_fire(&mut self) {
-        let mut to_burn = Vec::new();
-
-        // For all undefended neighbours that are not already burning, check whether they have
-        // to be added to `to_burn`
-        self.is_active = false;
-        for node_data in self.node_data.get_burning_node_data() {
-            for edge in self.graph.get_outgoing_edges(node_data.node_id) {
-                if self.node_data.is_undefended(&edge.tgt) {
-                    // There is at least one node to be burned at some point in the future
-                    if !self.is_active {
-                        self.is_active = true;
-                    }
-                    // Burn the node if the global time exceeds the time at which the edge source
-                    // started burning plus the edge weight
-                    if self.global_time >= node_data.time + edge.dist as TimeUnit {
-                        to_burn.push(edge.tgt);
-                    }
+        let graph = &self.graph;
+        let settings = &self.settings;
+        let rng = &mut self.rng;
+        let spread_delays = &mut self.spread_delays;
+        self.is_active = spread_fire(graph, settings, &mut self.node_data, self.global_time,
+                                     |edge, ignition_time| spread_delay(graph, settings, rng,
+                                                                        spread_delays, edge,
+                                                                        ignition_time));
+    }
+
+    /// Record the size of the fire front at the current time. The size is only recomputed if
+    /// the fire, the defense or the drawn spread delays changed since it was last computed or
+    /// nodes may have burned out.
+    fn record_fire_front(&mut self) {
+        let state = (self.node_data.burning.len(), self.node_data.defended.len(),
+                     self.node_data.protected.len(), self.spread_delays.len());
+        let size = match self.fire_front.last() {
+            Some(&size) if state == self.fire_front_state && self.settings.burn_duration.is_none() => size,
+            _ => self.fire_front_size(),
+        };
+        self.fire_front_state = state;
+        self.fire_front.push(size);
+    }
+
+    /// Get the number of undefended nodes that are adjacent to a burning node via an edge that
+    /// is not blocked and along which the fire may still spread, i.e. the size of the fire front.
+    /// Edges whose spread delay has not been drawn yet are assumed to spread the fire.
+    fn fire_front_size(&self) -> usize {
+        let mut front = Vec::new();
+        for nd in self.node_data.burning.values() {
+            if self.node_data.is_burned_out_by(&nd.node_id, &self.global_time) {
+                continue;
+            }
+            for edge in self.graph.get_outgoing_edges(nd.node_id) {
+                if !self.node_data.is_undefended(&edge.tgt)
+                    || self.node_data.is_blocked(&edge.src, &edge.tgt) {
+                    continue;
+                }
+                let spreads = match drawn_spread_delay(&self.graph, &self.settings,
+                                                       &self.spread_delays, edge, nd.time) {
+                    Some(delay) => delay.is_some_and(|delay|
+                        spreads_before_burn_out(&self.settings, delay)),
+                    None => true,
+                };
+                if spreads {
+                    front.push(edge.tgt);
                 }
             }
         }
+        front.sort_unstable();
+        front.dedup();
+        front.len()
+    }
 
-        // Burn all nodes in `to_burn`
-        self.node_data.mark_burning(&to_burn, self.global_time);
+    /// Get the total length of all roads between burned nodes. Roads that are represented by
+    /// an edge in both directions are counted once.
+    fn burned_road_length(&self) -> usize {
+        self.graph.edges().iter()
+            .filter(|edge| self.node_data.is_burning(&edge.src) && self.node_data.is_burning(&edge.tgt))
+            .filter(|edge| edge.src < edge.tgt || !self.graph.get_outgoing_edges(edge.tgt).iter()
+                .any(|rev| rev.tgt == edge.src))
+            .map(|edge| edge.dist)
+            .sum()
+    }
+
+    /// Count the connected components of the subgraph induced by the nodes that did not burn,
+    /// ignoring the direction of the edges
+    fn count_saved_components(&self) -> usize {
+        // Union-find with path halving
+        let mut parents: Vec<_> = (0..self.graph.num_nodes).collect();
+        fn find(parents: &mut [usize], mut node_id: usize) -> usize {
+            while parents[node_id] != node_id {
+                parents[node_id] = parents[parents[node_id]];
+                node_id = parents[node_id];
+            }
+            node_id
+        }
+
+        for edge in self.graph.edges() {
+            if !self.node_data.is_burning(&edge.src) && !self.node_data.is_burning(&edge.tgt) {
+                let src_root = find(&mut parents, edge.src);
+                let tgt_root = find(&mut parents, edge.tgt);
+                parents[src_root] = tgt_root;
+            }
+        }
+
+        (0..self.graph.num_nodes)
+            .filter(|node_id| !self.node_data.is_burning(node_id) && parents[*node_id] == *node_id)
+            .count()
     }
 
     /// Execute the containment strategy to prevent as much nodes as
     /// possible from catching fire
     fn contain_fire(&mut self) {
-        if self.global_time % self.settings.strategy_every == 0 {
-            self.strategy.mut_inner().execute(&self.settings, &mut self.node_data, self.global_time);
+        if self.plan.is_some() {
+            self.check_plan_defenses(self.global_time, true);
+        } else if let Some(ref mut strategy) = self.strategy {
+            if self.settings.firefighters_at(self.global_time) > 0 {
+                strategy.execute(&self.settings, &mut self.node_data, self.global_time);
+                self.strategy_rounds += 1;
+            }
+        }
+        if self.node_data.is_mobile() {
+            self.dispatch_firefighters();
+            self.node_data.advance_firefighters(self.global_time);
+        }
+    }
+
+    /// Dispatch idle firefighters to the nodes that the strategy ordered to defend.
+    /// Each order is assigned to the idle firefighter that can reach the node first.
+    /// Orders that cannot be assigned are dropped.
+    fn dispatch_firefighters(&mut self) {
+        let orders = self.node_data.take_defense_orders();
+        if orders.is_empty() {
+            return;
+        }
+        let speed = self.settings.mobility.as_ref().unwrap().speed;
+
+        // Compute shortest path trees from the positions of all idle firefighters
+        let mut idle: Vec<_> = self.node_data.firefighters.iter()
+            .filter(|ff| ff.is_idle())
+            .map(|ff| {
+                let (dists, preds) = self.graph.run_dijkstra_with_preds(&[ff.position()]);
+                (ff.id, dists, preds)
+            })
+            .collect();
+
+        for node_id in orders {
+            if !self.node_data.is_undefended(&node_id) || self.node_data.is_targeted(&node_id) {
+                continue;
+            }
+
+            let closest = idle.iter()
+                .enumerate()
+                .filter(|(_, (_, dists, _))| dists[node_id] < usize::MAX)
+                .min_by_key(|(_, (_, dists, _))| dists[node_id])
+                .map(|(i, _)| i);
+            let (ff_id, dists, preds) = match closest {
+                Some(i) => idle.swap_remove(i),
+                None => {
+                    log::debug!("No idle firefighter can reach node {} in round {}",
+                        node_id, self.global_time);
+                    continue;
+                }
+            };
+
+            // Reconstruct the route from the firefighter's position to the node
+            let mut path = vec![node_id];
+            while preds[*path.last().unwrap()] != usize::MAX {
+                path.push(preds[*path.last().unwrap()]);
+            }
+            let route: Vec<_> = path.into_iter()
+                .rev()
+                .skip(1)
+                .map(|node_id| NodeData {
+                    node_id,
+                    time: self.global_time + (dists[node_id] as f64 / speed).ceil() as TimeUnit,
+                })
+                .collect();
+
+            log::debug!("Dispatching firefighter {} to node {} in round {}",
+                ff_id, node_id, self.global_time);
+            self.node_data.firefighters[ff_id].dispatch(node_id, route, self.global_time);
         }
     }
 
@@ -280,11 +1753,82 @@ impl OSMFProblem {
     fn exec_step(&mut self) {
         self.global_time += 1;
 
+        self.ignite_secondary_fires();
         self.contain_fire();
         self.spread_fire();
+        self.record_fire_front();
+
+        // Keep the simulation running until all scheduled ignitions happened, unless it
+        // should end as soon as the fire is contained
+        if !self.is_active {
+            let ignitions_pending = self.settings.ignitions.scheduled.iter()
+                .any(|ig| ig.time > self.global_time);
+            if ignitions_pending && !self.settings.termination.stop_when_contained {
+                self.is_active = true;
+            } else if ignitions_pending {
+                self.end_reason = Some(OSMFEndReason::Contained);
+            } else {
+                self.end_reason = Some(OSMFEndReason::FireStopped);
+            }
+        }
+    }
+
+    /// Check whether the simulation has to end before the next step because of the
+    /// termination conditions in the settings, which started at `start`
+    fn check_termination(&self, start: &Instant) -> Option<OSMFEndReason> {
+        let termination = &self.settings.termination;
+        if termination.max_time.is_some_and(|max_time| self.global_time >= max_time) {
+            Some(OSMFEndReason::MaxTime)
+        } else if termination.max_strategy_rounds.is_some_and(|max| self.strategy_rounds >= max) {
+            Some(OSMFEndReason::MaxStrategyRounds)
+        } else if termination.max_millis.is_some_and(|max| start.elapsed().as_millis() >= max as u128) {
+            Some(OSMFEndReason::MaxMillis)
+        } else {
+            None
+        }
     }
 
-    /// Simulate the firefighter problem until the `is_active` flag is set to `false`
+    /// Ignite all nodes scheduled to catch fire at the current time and, with the configured
+    /// probability, a random undefended node.
+    /// The containment strategy is notified about the new ignitions.
+    fn ignite_secondary_fires(&mut self) {
+        let mut ignited: Vec<_> = self.settings.ignitions.scheduled.iter()
+            .filter(|ig| ig.time == self.global_time && self.node_data.is_undefended(&ig.node_id))
+            .map(|ig| ig.node_id)
+            .collect();
+
+        let ignitions = &self.settings.ignitions;
+        if ignitions.random_probability > 0.0 && self.rng.gen_bool(ignitions.random_probability) {
+            let dists = ignitions.spot_distance
+                .map(|_| self.graph.run_dijkstra(&self.node_data.get_burning()));
+            let candidates: Vec<_> = self.graph.nodes().iter()
+                .map(|node| node.id)
+                .filter(|node_id| self.node_data.is_undefended(node_id) && !ignited.contains(node_id))
+                .filter(|&node_id| match (&dists, ignitions.spot_distance) {
+                    (Some(dists), Some(spot_distance)) => dists[node_id] <= spot_distance,
+                    _ => true
+                })
+                .collect();
+            if let Some(&node_id) = candidates.choose(&mut self.rng) {
+                ignited.push(node_id);
+            }
+        }
+
+        if ignited.is_empty() {
+            return;
+        }
+
+        log::debug!("Secondary ignitions {:?} in round {}", ignited, self.global_time);
+        ignited.sort_unstable();
+        ignited.dedup();
+        self.node_data.mark_ignited(&ignited, self.global_time);
+        if let Some(ref mut strategy) = self.strategy {
+            strategy.notify_ignitions(&ignited, &self.settings, &self.node_data, self.global_time);
+        }
+    }
+
+    /// Simulate the firefighter problem until the `is_active` flag is set to `false` or a
+    /// termination condition is met
     pub fn simulate(&mut self) {
         if !self.is_active {
             return;
@@ -292,38 +1836,119 @@ impl OSMFProblem {
 
         log::info!("Starting problem simulation");
 
-        let roots = self.gen_fire_roots();
+        self.place_firebreaks();
+        let roots = match self.plan {
+            Some(_) => self.ignite_plan_roots(),
+            None => self.gen_fire_roots(),
+        };
+        self.record_fire_front();
 
         // Measure simulation time
         let start = Instant::now();
 
-        self.strategy.initialize(&roots, &self.settings, &self.node_data);
-        log::info!("Initialized fire containment strategy");
+        if let Some(ref mut strategy) = self.strategy {
+            strategy.initialize(&roots, &self.settings, &self.node_data);
+            log::info!("Initialized fire containment strategy");
+        }
 
         while self.is_active {
+            if let Some(end_reason) = self.check_termination(&start) {
+                self.is_active = false;
+                self.end_reason = Some(end_reason);
+                break;
+            }
             self.exec_step();
         }
 
+        log::info!("Simulation ended. end_reason={:?}.", self.end_reason);
+        // Planned defenses after the end of the simulation have no effect, but are still
+        // checked against the final state
+        let late_defense_times: Vec<_> = self.plan.iter()
+            .flat_map(|plan| plan.defenses.range(self.global_time + 1..).map(|(&time, _)| time))
+            .collect();
+        for time in late_defense_times {
+            self.check_plan_defenses(time, false);
+        }
+        self.node_data.build_time_index(self.global_time);
         self.simulation_time_millis = start.elapsed().as_millis();
 
         log::info!("Finished problem simulation");
     }
 
+    /// Get the fire containment strategy of this firefighter problem instance, or `None` if it
+    /// replays an external defense plan
+    pub fn strategy(&self) -> Option<&OSMFStrategy> {
+        self.strategy.as_ref()
+    }
+
     /// Generate the simulation response for this firefighter problem instance
     pub fn simulation_response(&self) -> OSMFSimulationResponse {
         log::info!("Generating simulation response");
 
+        let weight_burned: f64 = self.node_data.burning.keys()
+            .map(|&node_id| self.graph.get_node_weight(node_id))
+            .sum();
+        let weight_defended: f64 = self.node_data.defended.keys()
+            .filter(|node_id| !self.node_data.is_firebreak(node_id))
+            .map(|&node_id| self.graph.get_node_weight(node_id))
+            .sum();
+        let weight_total = self.graph.total_weight();
+        let nodes_saved = self.graph.num_nodes - self.node_data.burning.len();
+        let ff_efficiency = match self.node_data.defended.len() {
+            0 => None,
+            num_defended => Some(nodes_saved as f64 / num_defended as f64),
+        };
+        let targets: Vec<_> = self.settings.target_nodes(&self.graph).into_iter()
+            .map(|node_id| OSMFTargetStatus {
+                node_id,
+                burned_at: self.node_data.get_burning_time(&node_id),
+            })
+            .collect();
+
         OSMFSimulationResponse {
             nodes_burned: self.node_data.burning.len(),
-            nodes_defended: self.node_data.defended.len(),
+            nodes_defended: self.node_data.defended.len() - self.node_data.count_firebreaks(),
+            firebreaks: self.node_data.count_firebreaks(),
+            edges_protected: self.node_data.protected.len(),
             nodes_total: self.graph.num_nodes,
+            weight_burned,
+            weight_defended,
+            weight_saved: weight_total - weight_burned,
+            weight_total,
+            targets_burned: targets.iter().filter(|t| t.burned_at.is_some()).count(),
+            targets,
+            containment_time: match self.fire_front.last() {
+                Some(0) => Some(self.fire_front.iter()
+                    .rposition(|&size| size > 0)
+                    .map_or(0, |time| time as TimeUnit + 1)),
+                _ => None,
+            },
+            fire_front: &self.fire_front,
+            burned_road_length: self.burned_road_length(),
+            saved_components: self.count_saved_components(),
+            ff_efficiency,
             end_time: self.global_time,
+            end_reason: self.end_reason,
             simulation_time_millis: self.simulation_time_millis,
+            seed: self.seed,
             view_bounds: &self.view.grid_bounds,
             view_center: self.view.initial_center,
         }
     }
 
+    /// Generate the validation response for the external defense plan of this firefighter
+    /// problem instance
+    pub fn plan_validation_response(&self) -> OSMFPlanValidation<'_> {
+        log::info!("Generating plan validation response");
+
+        let valid = self.plan_violations.is_empty();
+        OSMFPlanValidation {
+            valid,
+            violations: &self.plan_violations,
+            results: valid.then(|| self.simulation_response()),
+        }
+    }
+
     /// Generate the view response for this firefighter problem instance
     pub fn view_response(&mut self, center: Coords, zoom: f64, time: &TimeUnit) -> Vec<u8> {
         log::info!("Generating view response. center={:?}, zoom={}, time={}.", center, zoom, time);
@@ -340,12 +1965,165 @@ impl OSMFProblem {
         self.view.png_bytes()
     }
 
+    /// Generate the time series response for this firefighter problem instance
+    pub fn time_series_response(&self) -> OSMFTimeSeries {
+        log::info!("Generating time series response");
+
+        let built_index;
+        let index = match self.node_data.time_index {
+            Some(ref index) => index,
+            None => {
+                built_index = TimeIndex::new(&self.node_data.burning, &self.node_data.defended,
+                                             self.global_time);
+                &built_index
+            }
+        };
+
+        let times = 0..=self.global_time;
+        OSMFTimeSeries {
+            end_time: self.global_time,
+            burned_cumulative: times.clone().map(|time| index.burning_by(&time)).collect(),
+            burned_new: times.clone().map(|time| index.burning_at(&time).len()).collect(),
+            defended_cumulative: times.clone().map(|time| index.defended_by(&time)).collect(),
+            defended_new: times.map(|time| index.defended_at(&time).len()).collect(),
+        }
+    }
+
+    /// Export the complete results of this firefighter problem instance
+    pub fn to_record(&self) -> OSMFSimulationRecord {
+        let node_times = |data: &LayeredMap<usize, NodeData>| data.values()
+            .map(|nd| (nd.node_id, nd.time))
+            .collect();
+
+        OSMFSimulationRecord {
+            version: RECORD_VERSION,
+            settings: self.settings.clone(),
+            seed: self.seed,
+            graph: OSMFGraphIdentity::of(&self.settings.graph_name, &self.graph),
+            roots: self.node_data.roots.iter().copied().collect(),
+            late_ignitions: self.node_data.late_ignitions.iter().copied().collect(),
+            burned: node_times(&self.node_data.burning),
+            defended: node_times(&self.node_data.defended),
+            protected: self.node_data.protected.values()
+                .map(|ed| (ed.src, ed.tgt, ed.time))
+                .collect(),
+            fire_front: self.fire_front.clone(),
+            end_time: self.global_time,
+            end_reason: self.end_reason,
+            simulation_time_millis: self.simulation_time_millis,
+        }
+    }
+
+    /// Create a firefighter problem instance from exported results without re-running the
+    /// simulation. The routes of mobile firefighters are not part of the results.
+    pub fn from_record(graph: Arc<Graph>, record: OSMFSimulationRecord) -> Result<Self, OSMFRecordError> {
+        let identity = OSMFGraphIdentity::of(&record.settings.graph_name, &graph);
+        if identity != record.graph {
+            return Err(OSMFRecordError::GraphMismatch {
+                graph_name: identity.name,
+                expected_hash: record.graph.content_hash,
+                actual_hash: identity.content_hash,
+            });
+        }
+
+        let invalid = |reason: String| OSMFRecordError::InvalidRecord { reason };
+        let node_ids = record.roots.iter()
+            .chain(&record.late_ignitions)
+            .chain(record.burned.iter().map(|(node_id, _)| node_id))
+            .chain(record.defended.iter().map(|(node_id, _)| node_id))
+            .chain(record.protected.iter().flat_map(|(src, tgt, _)| [src, tgt]));
+        if let Some(node_id) = node_ids.into_iter().find(|&&node_id| node_id >= graph.num_nodes) {
+            return Err(invalid(format!("node id {} out of range", node_id)));
+        }
+        let mut times = record.burned.iter().map(|(_, time)| time)
+            .chain(record.defended.iter().map(|(_, time)| time))
+            .chain(record.protected.iter().map(|(_, _, time)| time));
+        if let Some(time) = times.find(|&&time| time > record.end_time) {
+            return Err(invalid(format!("time {} exceeds end time {}", time, record.end_time)));
+        }
+
+        // The fire front has an entry for every time up to the end time, so the end time is
+        // bounded by the size of the record
+        if (record.fire_front.len() as TimeUnit).checked_sub(1) != Some(record.end_time) {
+            return Err(invalid(format!("fire front has {} entries instead of end time {} + 1",
+                                       record.fire_front.len(), record.end_time)));
+        }
+        let burned: HashSet<_> = record.burned.iter().map(|&(node_id, _)| node_id).collect();
+        if let Some((node_id, _)) = record.defended.iter().find(|(node_id, _)| burned.contains(node_id)) {
+            return Err(invalid(format!("node {} is both burned and defended", node_id)));
+        }
+
+        // Viewing the results does not need the strategy, which may be unknown to this server
+        let mut problem = Self::with_strategy(graph, record.settings, None)?;
+
+        let mut node_data = NodeDataStorage::new(problem.settings.defense_mode,
+                                                 problem.settings.burn_duration);
+        for (node_id, time) in record.burned {
+            node_data.burning.insert(node_id, NodeData { node_id, time });
+        }
+        for (node_id, time) in record.defended {
+            if time == 0 {
+                Arc::make_mut(&mut node_data.firebreaks).insert(node_id);
+            }
+            node_data.defended.insert(node_id, NodeData { node_id, time });
+        }
+        for (src, tgt, time) in record.protected {
+            node_data.protected.insert((src, tgt), EdgeData { src, tgt, time });
+        }
+        for node_id in record.roots {
+            if node_data.get_burning_time(&node_id) != Some(0) {
+                return Err(invalid(format!("fire root {} is not burning at time 0", node_id)));
+            }
+            Arc::make_mut(&mut node_data.roots).insert(node_id);
+        }
+        for node_id in record.late_ignitions {
+            if !node_data.is_burning(&node_id) {
+                return Err(invalid(format!("late ignition {} is not burning", node_id)));
+            }
+            Arc::make_mut(&mut node_data.late_ignitions).insert(node_id);
+        }
+        node_data.build_time_index(record.end_time);
+
+        problem.node_data = node_data;
+        problem.global_time = record.end_time;
+        problem.simulation_time_millis = record.simulation_time_millis;
+        problem.is_active = false;
+        problem.end_reason = record.end_reason;
+        problem.fire_front = record.fire_front;
+        problem.settings.seed = Some(record.seed);
+        problem.seed = record.seed;
+        problem.rng = StdRng::seed_from_u64(record.seed);
+
+        log::info!("Imported simulation results. end_time={}.", problem.global_time);
+
+        Ok(problem)
+    }
+
     pub fn sim_step_metadata_response(&self, time: &TimeUnit) -> OSMFSimulationStepMetadata {
         log::info!("Generating simulation step metadata response. time={}.", time);
 
+        let nodes_burned_by = self.node_data.count_burning_by(time);
+        // Firebreaks are defended at time `0` and are counted separately
+        let firebreaks = self.node_data.count_firebreaks();
+        let nodes_defended_by = self.node_data.count_defended_by(time) - firebreaks;
+        let nodes_burned_out_by = self.node_data.count_burned_out_by(time);
+
         OSMFSimulationStepMetadata {
-            nodes_burned_by: self.node_data.count_burning_by(time),
-            nodes_defended_by: self.node_data.count_defended_by(time),
+            nodes_burned_by,
+            nodes_defended_by,
+            firebreaks,
+            edges_protected_by: self.node_data.count_protected_by(time),
+            fire_front: self.fire_front.get(*time as usize)
+                .or_else(|| self.fire_front.last())
+                .copied()
+                .unwrap_or(0),
+            node_states: OSMFNodeStateCounts {
+                susceptible: self.graph.num_nodes - nodes_burned_by - nodes_defended_by - firebreaks,
+                burning: nodes_burned_by - nodes_burned_out_by,
+                burned_out: nodes_burned_out_by,
+                defended: nodes_defended_by,
+                firebreak: firebreaks,
+            },
             nodes_burned_at: self.node_data.get_burning_at(time),
             nodes_defended_at: self.node_data.get_defended_at(time),
         }
@@ -358,14 +2136,26 @@ mod test {
 
     use once_cell::sync::Lazy;
 
-    use crate::firefighter::{problem::{OSMFProblem, OSMFSettings},
+    use crate::firefighter::{problem::{OSMFDelayDistribution, OSMFProblem, OSMFSettings,
+                                       Firefighter, NodeData, OSMFDefenseMode, OSMFDefensePlan, OSMFDeployment,
+                                       OSMFEndReason, OSMFPlannedDefense, OSMFPlanViolation,
+                                       OSMFFirebreakStrategy, OSMFFirefighterSchedule,
+                                       OSMFIgnition, OSMFMobility,
+                                       OSMFObjective, OSMFRegion, OSMFSettingsError,
+                                       OSMFSpreadModel, OSMFTargets, OSMFWind,
+                                       MAX_WIND_STRENGTH},
                              strategy::{OSMFStrategy,
+                                        DominatorStrategy,
                                         GreedyStrategy,
+                                        GreedyEdgesStrategy,
                                         MultiMinDistSetsStrategy,
                                         RandomStrategy,
                                         PriorityStrategy,
                                         Strategy}};
     use crate::firefighter::strategy::ScoreStrategy;
+    use crate::firefighter::TimeUnit;
+    use crate::firefighter::tree::FireTree;
+    use crate::centrality::Centrality;
     use crate::graph::Graph;
 
     struct TestData {
@@ -382,6 +2172,7 @@ mod test {
                 num_roots: 10,
                 num_ffs: 2,
                 strategy_every: 10,
+                ..OSMFSettings::default()
             },
         });
 
@@ -389,6 +2180,40 @@ mod test {
         OSMFProblem::new(TEST_DATA.graph.clone(), TEST_DATA.settings.clone(), strategy).unwrap()
     }
 
+    /// Simulate the strategy `strategy_name` with the parameters `params` on the test data
+    fn simulate_strategy(strategy_name: &str, seed: u64, params: &[(&str, &str)]) -> OSMFProblem {
+        let settings = OSMFSettings {
+            strategy_name: strategy_name.to_string(),
+            seed: Some(seed),
+            strategy_params: params.iter()
+                .map(|&(name, value)| (name.to_string(), value.parse().unwrap()))
+                .collect(),
+            ..TEST_DATA.settings.clone()
+        };
+        let strategy = OSMFStrategy::from_name_and_graph(strategy_name, TEST_DATA.graph.clone())
+            .unwrap();
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, strategy).unwrap();
+        problem.simulate();
+        problem
+    }
+
+    /// Assert that the simulation of `problem` ended and never defended more nodes than the
+    /// firefighters allowed
+    fn assert_within_budget(problem: &OSMFProblem) {
+        let response = problem.simulation_response();
+        assert!(!problem.is_active);
+        assert!(response.nodes_defended <= problem.settings.firefighters_by(response.end_time));
+        assert!(problem.time_series_response().defended_new.iter()
+            .all(|&num_defended| num_defended <= problem.settings.num_ffs));
+    }
+
+    /// Get the nodes that were burning when the strategy of `problem` was executed at `time`
+    fn burning_before(problem: &OSMFProblem, time: TimeUnit) -> Vec<usize> {
+        problem.node_data.get_burning().into_iter()
+            .filter(|node_id| problem.node_data.get_burning_time(node_id).unwrap() < time)
+            .collect()
+    }
+
     #[test]
     fn test_roots() {
         let mut problem = initialize(OSMFStrategy::Random(
@@ -524,4 +2349,1008 @@ mod test {
             .count();
         assert_eq!(num_ambiguous, 0, "num ambiguous: {}", num_ambiguous);
     }
+
+    #[test]
+    fn test_zero_ignition_probability() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.spread_model = OSMFSpreadModel::Probabilistic {
+            ignition_probability: 0.0,
+            delay: None,
+        };
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        assert_eq!(problem.node_data.burning.len(), problem.settings.num_roots);
+        assert!(!problem.is_active);
+    }
+
+    #[test]
+    fn test_seeded() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.seed = Some(7);
+        settings.spread_model = OSMFSpreadModel::Probabilistic {
+            ignition_probability: 0.5,
+            delay: Some(OSMFDelayDistribution::Uniform { min: 0, max: 20 }),
+        };
+
+        // The random strategy draws from the seed of the simulation as well
+        for strategy_name in ["Greedy", "Random"] {
+            let mut results = Vec::new();
+            for _ in 0..2 {
+                let strategy = OSMFStrategy::from_name_and_graph(strategy_name,
+                                                                 TEST_DATA.graph.clone()).unwrap();
+                let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                                   strategy).unwrap();
+                problem.simulate();
+                results.push((problem.node_data.get_burning(), problem.node_data.get_defended()));
+            }
+            assert_eq!(results[0], results[1]);
+        }
+    }
+
+    #[test]
+    fn test_invalid_ignition_probability() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.spread_model = OSMFSpreadModel::Probabilistic {
+            ignition_probability: 1.5,
+            delay: None,
+        };
+        let problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone())));
+
+        assert!(problem.is_err());
+    }
+
+    #[test]
+    fn test_wind() {
+        let graph = &TEST_DATA.graph;
+        let edge = graph.edges().iter()
+            .max_by_key(|edge| edge.dist)
+            .unwrap();
+        let bearing = graph.get_node(edge.src).bearing_to(graph.get_node(edge.tgt));
+
+        let mut settings = TEST_DATA.settings.clone();
+        settings.wind_schedule = vec![
+            OSMFWind { time: 0, direction: bearing, strength: 1.0 },
+            OSMFWind { time: 5, direction: (bearing + 180.0) % 360.0, strength: 1.0 },
+            OSMFWind { time: 10, direction: bearing, strength: 0.0 },
+        ];
+        let mut problem = OSMFProblem::new(graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(graph.clone()))).unwrap();
+
+        let dist = edge.dist as u64;
+        assert!(problem.spread_delay(edge, 0).unwrap() < dist);
+        assert!(problem.spread_delay(edge, 7).unwrap() > dist);
+        assert_eq!(problem.spread_delay(edge, 12).unwrap(), dist);
+    }
+
+    #[test]
+    fn test_invalid_wind_schedule() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.wind_schedule = vec![
+            OSMFWind { time: 5, direction: 0.0, strength: 1.0 },
+            OSMFWind { time: 5, direction: 90.0, strength: 1.0 },
+        ];
+        let problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone())));
+
+        assert!(problem.is_err());
+
+        // The spread delays are bounded, so the strength of the wind is
+        for (strength, is_valid) in [(MAX_WIND_STRENGTH, true), (MAX_WIND_STRENGTH + 1.0, false),
+                                     (f64::MAX, false)] {
+            let mut settings = TEST_DATA.settings.clone();
+            settings.wind_schedule = vec![OSMFWind { time: 0, direction: 0.0, strength }];
+            assert_eq!(settings.validate(&TEST_DATA.graph).is_ok(), is_valid);
+        }
+        let mut settings = TEST_DATA.settings.clone();
+        settings.spread_model = OSMFSpreadModel::Probabilistic {
+            ignition_probability: 1.0,
+            delay: Some(OSMFDelayDistribution::Geometric { mean: 1e300 }),
+        };
+        assert!(settings.validate(&TEST_DATA.graph).is_err());
+    }
+
+    #[test]
+    fn test_mobility() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.mobility = Some(OSMFMobility {
+            stations: vec![0, 100],
+            speed: 5.0,
+        });
+
+        for strategy in [
+            OSMFStrategy::Greedy(GreedyStrategy::new(TEST_DATA.graph.clone())),
+            OSMFStrategy::MultiMinDistanceSets(MultiMinDistSetsStrategy::new(TEST_DATA.graph.clone())),
+        ] {
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(), strategy)
+                .unwrap();
+            problem.simulate();
+
+            let firefighters = problem.node_data.firefighters();
+            assert_eq!(firefighters.len(), settings.num_ffs);
+            assert_eq!(firefighters[1].station, 100);
+
+            // Every defended node must have been reached by a firefighter by the time it
+            // was defended
+            for nd in problem.node_data.defended.values() {
+                let reached = firefighters.iter()
+                    .any(|ff| ff.position_at(&nd.time).0 == nd.node_id);
+                assert!(reached, "node {} defended at {} was not reached", nd.node_id, nd.time);
+            }
+        }
+
+        // Firefighters leave their position when they are dispatched, not when they arrived
+        let mut firefighter = Firefighter::new(0, 5);
+        firefighter.dispatch(7, vec![NodeData { node_id: 6, time: 12 },
+                                     NodeData { node_id: 7, time: 14 }], 10);
+        assert_eq!(firefighter.position_at(&4), (5, 5, 0.0));
+        assert_eq!(firefighter.position_at(&10), (5, 6, 0.0));
+        assert_eq!(firefighter.position_at(&11), (5, 6, 0.5));
+        assert_eq!(firefighter.position_at(&13), (6, 7, 0.5));
+        assert_eq!(firefighter.position_at(&20), (7, 7, 0.0));
+    }
+
+    #[test]
+    fn test_block_edges() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.defense_mode = OSMFDefenseMode::BlockEdges;
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::GreedyEdges(
+            GreedyEdgesStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        assert_eq!(problem.node_data.defended.len(), 0);
+        let num_protected = problem.node_data.protected.len();
+        let should_protected = problem.settings.num_ffs
+            * (problem.global_time / problem.settings.strategy_every) as usize;
+        assert!(num_protected <= should_protected, "num protected: {}, should protected: {}",
+                num_protected, should_protected);
+
+        // Every burned node must have been reached along an edge that was not blocked
+        let graph = &TEST_DATA.graph;
+        for nd in problem.node_data.burning.values().filter(|nd| nd.time > 0) {
+            let reached = graph.edges().iter()
+                .filter(|e| e.tgt == nd.node_id)
+                .any(|e| match problem.node_data.burning.get(&e.src) {
+                    Some(src_nd) => src_nd.time + e.dist as u64 <= nd.time
+                        && !problem.node_data.is_blocked_by(&e.src, &e.tgt, &nd.time),
+                    None => false
+                });
+            assert!(reached, "node {} burned across a blocked edge", nd.node_id);
+        }
+    }
+
+    #[test]
+    fn test_orient_edges() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.defense_mode = OSMFDefenseMode::OrientEdges;
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::GreedyEdges(
+            GreedyEdgesStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+
+        problem.node_data.mark_protected(&[(0, 210)], 0);
+        assert!(problem.node_data.is_blocked(&0, &210));
+        assert!(!problem.node_data.is_blocked(&210, &0));
+    }
+
+    #[test]
+    fn test_incompatible_strategy() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.defense_mode = OSMFDefenseMode::BlockEdges;
+        let problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone())));
+
+        assert!(problem.is_err());
+    }
+
+    #[test]
+    fn test_burn_out() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.burn_duration = Some(30);
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        // Fire spread only along edges that are shorter than the burn duration
+        let graph = &TEST_DATA.graph;
+        for nd in problem.node_data.burning.values().filter(|nd| nd.time > 0) {
+            let reached = graph.edges().iter()
+                .filter(|e| e.tgt == nd.node_id && e.dist < 30)
+                .any(|e| problem.node_data.is_burning_by(&e.src, &nd.time));
+            assert!(reached, "node {} caught fire from a burned out node", nd.node_id);
+        }
+
+        for time in [0, 15, 30, problem.global_time + 30] {
+            let metadata = problem.sim_step_metadata_response(&time);
+            let states = &metadata.node_states;
+            assert_eq!(states.susceptible + states.burning + states.burned_out + states.defended,
+                       graph.num_nodes);
+            assert_eq!(states.burned_out, problem.node_data.count_burned_out_by(&time));
+        }
+        let final_states = problem.sim_step_metadata_response(&(problem.global_time + 30)).node_states;
+        assert_eq!(final_states.burning, 0);
+    }
+
+    #[test]
+    fn test_secondary_ignitions() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.num_roots = 0;
+        settings.ignitions.scheduled = vec![
+            OSMFIgnition { time: 20, node_id: 5 },
+            OSMFIgnition { time: 40, node_id: 200 },
+        ];
+
+        for strategy in [
+            OSMFStrategy::MultiMinDistanceSets(MultiMinDistSetsStrategy::new(TEST_DATA.graph.clone())),
+            OSMFStrategy::Priority(PriorityStrategy::new(TEST_DATA.graph.clone())),
+            OSMFStrategy::Dominator(DominatorStrategy::new(TEST_DATA.graph.clone())),
+        ] {
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(), strategy)
+                .unwrap();
+            problem.simulate();
+
+            let node_data = &problem.node_data;
+            assert!(node_data.get_roots().is_empty());
+            assert_eq!(node_data.get_burning_time(&5), Some(20));
+            assert!(node_data.is_late_ignition(&5) && !node_data.is_root(&5));
+            assert!(node_data.get_burning_time(&200).map_or(node_data.is_defended(&200), |t| t <= 40));
+            assert!(problem.global_time >= 40);
+
+            let num_ambiguous = node_data.burning.keys()
+                .filter(|&node_id| node_data.defended.contains_key(node_id))
+                .count();
+            assert_eq!(num_ambiguous, 0, "num ambiguous: {}", num_ambiguous);
+        }
+    }
+
+    #[test]
+    fn test_random_ignitions() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.spread_model = OSMFSpreadModel::Probabilistic {
+            ignition_probability: 0.0,
+            delay: None,
+        };
+        settings.ignitions.random_probability = 1.0;
+        settings.ignitions.spot_distance = Some(100);
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        // Without spread, only roots and the random spot fires burn
+        let num_late = problem.node_data.late_ignitions.len();
+        assert!(num_late >= 1);
+        assert_eq!(problem.node_data.burning.len(), problem.settings.num_roots + num_late);
+    }
+
+    #[test]
+    fn test_saved_weight() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.objective = OSMFObjective::SavedWeight;
+        for strategy_name in ["Greedy", "Score", "MultiMinDistanceSets", "Priority"] {
+            let strategy = OSMFStrategy::from_name_and_graph(strategy_name,
+                                                             TEST_DATA.graph.clone()).unwrap();
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                               strategy).unwrap();
+            problem.simulate();
+
+            // With unit weights, the weighted results equal the node counts
+            let response = problem.simulation_response();
+            assert_eq!(response.weight_burned, response.nodes_burned as f64);
+            assert_eq!(response.weight_defended, response.nodes_defended as f64);
+            assert_eq!(response.weight_total, response.nodes_total as f64);
+            assert_eq!(response.weight_saved, response.weight_total - response.weight_burned);
+        }
+    }
+
+    #[test]
+    fn test_zero_weights() {
+        let mut graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        let path = std::env::temp_dir().join("osmff_test_zero_weights.weights");
+        let weights: String = (0..graph.num_nodes).map(|node_id| format!("{} 0\n", node_id)).collect();
+        std::fs::write(&path, format!("node\n{}", weights)).unwrap();
+        graph.load_weights_from_file(path.to_str().unwrap()).unwrap();
+        let graph = Arc::new(graph);
+
+        let mut settings = TEST_DATA.settings.clone();
+        settings.objective = OSMFObjective::SavedWeight;
+        settings.firebreaks.budget = 2;
+        for strategy_name in ["Greedy", "Score", "MultiMinDistanceSets", "Priority"] {
+            let strategy = OSMFStrategy::from_name_and_graph(strategy_name, graph.clone()).unwrap();
+            let mut problem = OSMFProblem::new(graph.clone(), settings.clone(), strategy).unwrap();
+            problem.simulate();
+
+            let response = problem.simulation_response();
+            assert!(!problem.is_active);
+            assert_eq!(response.weight_total, 0.0);
+            assert!(response.nodes_defended > 0);
+        }
+    }
+
+    #[test]
+    fn test_targets() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.strategy_name = "TargetCut".to_string();
+        settings.targets = OSMFTargets {
+            nodes: vec![7, 3, 7],
+            region: None,
+        };
+        assert_eq!(settings.target_nodes(&TEST_DATA.graph), vec![3, 7]);
+
+        let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name,
+                                                         TEST_DATA.graph.clone()).unwrap();
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                           strategy).unwrap();
+        problem.simulate();
+
+        let response = problem.simulation_response();
+        assert_eq!(response.targets.len(), 2);
+        for target in &response.targets {
+            assert_eq!(target.burned_at, problem.node_data.get_burning_time(&target.node_id));
+        }
+        assert_eq!(response.targets_burned,
+                   response.targets.iter().filter(|t| t.burned_at.is_some()).count());
+
+        // A region that covers the whole graph contains all nodes
+        settings.targets.region = Some(OSMFRegion {
+            min_lat: -90.0,
+            max_lat: 90.0,
+            min_lon: -180.0,
+            max_lon: 180.0,
+        });
+        assert_eq!(settings.target_nodes(&TEST_DATA.graph).len(), TEST_DATA.graph.num_nodes);
+
+        settings.targets.nodes.push(TEST_DATA.graph.num_nodes);
+        let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name,
+                                                         TEST_DATA.graph.clone()).unwrap();
+        assert!(OSMFProblem::new(TEST_DATA.graph.clone(), settings, strategy).is_err());
+    }
+
+    #[test]
+    fn test_firebreaks() {
+        let mut settings = TEST_DATA.settings.clone();
+        settings.firebreaks.nodes = vec![5, 1, 5];
+        settings.firebreaks.budget = 3;
+        for firebreak_strategy in [OSMFFirebreakStrategy::MostImportant, OSMFFirebreakStrategy::Random] {
+            settings.firebreaks.strategy = firebreak_strategy;
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                               OSMFStrategy::Greedy(GreedyStrategy::new(
+                                                   TEST_DATA.graph.clone()))).unwrap();
+            problem.simulate();
+
+            // Firebreaks are defended at time 0 and no fire root is a firebreak
+            let firebreaks = problem.node_data.get_defended_at(&0);
+            assert_eq!(firebreaks.len(), 5);
+            assert!(firebreaks.contains(&1) && firebreaks.contains(&5));
+            assert!(firebreaks.iter().all(|node_id| problem.node_data.is_firebreak(node_id)));
+            assert!(problem.node_data.get_roots().iter()
+                .all(|node_id| !problem.node_data.is_firebreak(node_id)));
+
+            let response = problem.simulation_response();
+            assert_eq!(response.firebreaks, 5);
+            assert_eq!(response.nodes_defended + response.firebreaks,
+                       problem.node_data.defended.len());
+        }
+
+        settings.firebreaks.budget = TEST_DATA.graph.num_nodes;
+        assert!(OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).is_err());
+    }
+
+    #[test]
+    fn test_ff_schedule() {
+        let mut settings = TEST_DATA.settings.clone();
+        let schedules = [
+            OSMFFirefighterSchedule::Constant,
+            OSMFFirefighterSchedule::Explicit {
+                deployments: vec![
+                    OSMFDeployment { time: 3, num_ffs: 1 },
+                    OSMFDeployment { time: 20, num_ffs: 4 },
+                ],
+            },
+            OSMFFirefighterSchedule::RampUp { initial: 1, increment: 2, max: 6 },
+        ];
+        for schedule in schedules {
+            settings.ff_schedule = schedule;
+
+            // The total number of firefighters is the sum of all deployments
+            let mut total = 0;
+            for time in 0..100 {
+                total += settings.firefighters_at(time);
+                assert_eq!(settings.firefighters_by(time), total);
+            }
+
+            for strategy_name in ["Greedy", "MultiMinDistanceSets", "SingleMinDistanceSet", "Priority"] {
+                let strategy = OSMFStrategy::from_name_and_graph(strategy_name,
+                                                                 TEST_DATA.graph.clone()).unwrap();
+                let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                                   strategy).unwrap();
+                problem.simulate();
+                assert!(problem.node_data.defended.len()
+                    <= settings.firefighters_by(problem.global_time));
+            }
+        }
+        assert_eq!(settings.firefighters_at(10), 1);
+        assert_eq!(settings.firefighters_at(30), 5);
+        assert_eq!(settings.firefighters_at(50), 6);
+        assert_eq!(settings.round_after(10, 3), 40);
+
+        // Rounds of explicit schedules do not depend on the period
+        let mut explicit = settings.clone();
+        explicit.strategy_every = 0;
+        explicit.ff_schedule = OSMFFirefighterSchedule::Explicit {
+            deployments: vec![
+                OSMFDeployment { time: 3, num_ffs: 1 },
+                OSMFDeployment { time: 5, num_ffs: 0 },
+                OSMFDeployment { time: 20, num_ffs: 4 },
+                OSMFDeployment { time: 42, num_ffs: 2 },
+            ],
+        };
+        assert_eq!(explicit.round_after(0, 1), 3);
+        assert_eq!(explicit.round_after(3, 1), 20);
+        assert_eq!(explicit.round_after(3, 2), 42);
+        assert_eq!(explicit.round_after(3, 4), 42);
+        assert_eq!(explicit.round_after(42, 4), 42);
+
+        // Schedules that deploy firefighters periodically require a positive period
+        settings.strategy_every = 0;
+        assert!(OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(), OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).is_err());
+        settings.ff_schedule = OSMFFirefighterSchedule::Explicit {
+            deployments: vec![OSMFDeployment { time: 0, num_ffs: 1 }],
+        };
+        assert!(OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).is_err());
+    }
+
+    #[test]
+    fn test_termination() {
+        let base_settings = OSMFSettings {
+            seed: Some(7),
+            ..TEST_DATA.settings.clone()
+        };
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), base_settings.clone(),
+                                           OSMFStrategy::Greedy(GreedyStrategy::new(
+                                               TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+        assert_eq!(problem.end_reason, Some(OSMFEndReason::FireStopped));
+        let end_time = problem.global_time;
+
+        let mut settings = base_settings.clone();
+        settings.termination.max_time = Some(end_time / 2);
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+        assert_eq!(problem.end_reason, Some(OSMFEndReason::MaxTime));
+        assert_eq!(problem.global_time, end_time / 2);
+
+        let mut settings = base_settings.clone();
+        settings.termination.max_strategy_rounds = Some(2);
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+        assert_eq!(problem.end_reason, Some(OSMFEndReason::MaxStrategyRounds));
+        assert_eq!(problem.global_time, 2 * problem.settings.strategy_every);
+
+        let mut settings = base_settings.clone();
+        settings.termination.max_millis = Some(0);
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+        assert_eq!(problem.end_reason, Some(OSMFEndReason::MaxMillis));
+        assert_eq!(problem.global_time, 0);
+
+        // A contained fire ends the simulation even though a secondary ignition is pending
+        let mut settings = base_settings.clone();
+        settings.ignitions.scheduled = vec![OSMFIgnition { time: 10 * end_time, node_id: 0 }];
+        settings.termination.stop_when_contained = true;
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+        assert_eq!(problem.end_reason, Some(OSMFEndReason::Contained));
+        assert_eq!(problem.global_time, end_time);
+    }
+
+    #[test]
+    fn test_containment_time() {
+        let settings = OSMFSettings {
+            seed: Some(3),
+            ..TEST_DATA.settings.clone()
+        };
+        let simulate = |settings: &OSMFSettings| {
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                               OSMFStrategy::Greedy(GreedyStrategy::new(TEST_DATA.graph.clone())))
+                .unwrap();
+            problem.simulate();
+            problem
+        };
+        let problem = simulate(&settings);
+        let first_containment = problem.simulation_response().containment_time.unwrap();
+
+        // Restart the fire after it was contained at the node with most undefended neighbors
+        let node_data = &problem.node_data;
+        let node_id = (0..TEST_DATA.graph.num_nodes)
+            .filter(|node_id| node_data.is_undefended(node_id))
+            .max_by_key(|&node_id| TEST_DATA.graph.get_outgoing_edges(node_id).iter()
+                .filter(|edge| node_data.is_undefended(&edge.tgt))
+                .count())
+            .unwrap();
+        let ignition_time = problem.global_time + 5;
+        let mut settings = settings.clone();
+        settings.ignitions.scheduled = vec![OSMFIgnition { time: ignition_time, node_id }];
+        let problem = simulate(&settings);
+
+        let response = problem.simulation_response();
+        assert_eq!(response.fire_front[first_containment as usize], 0);
+        assert!(response.fire_front[ignition_time as usize] > 0);
+        let containment_time = response.containment_time.unwrap();
+        assert!(containment_time > ignition_time);
+        assert!(response.fire_front[containment_time as usize..].iter().all(|&size| size == 0));
+        assert!(response.fire_front[containment_time as usize - 1] > 0);
+
+        // Edges that never ignite and edges that burn out first are not part of the fire front
+        for (seed, burn_duration) in [(1, None), (2, Some(30)), (3, Some(60))] {
+            let mut settings = settings.clone();
+            settings.seed = Some(seed);
+            settings.burn_duration = burn_duration;
+            settings.spread_model = OSMFSpreadModel::Probabilistic {
+                ignition_probability: 0.5,
+                delay: Some(OSMFDelayDistribution::Uniform { min: 0, max: 40 }),
+            };
+            let problem = simulate(&settings);
+            assert_eq!(problem.end_reason, Some(OSMFEndReason::FireStopped));
+            let response = problem.simulation_response();
+            assert_eq!(response.fire_front.last(), Some(&0));
+            assert!(response.containment_time.is_some());
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let settings = OSMFSettings {
+            seed: Some(3),
+            ..TEST_DATA.settings.clone()
+        };
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        let response = problem.simulation_response();
+        assert_eq!(response.fire_front.len() as TimeUnit, response.end_time + 1);
+        assert_eq!(response.fire_front.last(), Some(&0));
+        let containment_time = response.containment_time.unwrap();
+        assert!(containment_time <= response.end_time);
+        assert!(response.fire_front[..containment_time as usize].iter().all(|&size| size > 0));
+
+        // The fire spread along at least one road
+        assert!(response.burned_road_length > 0);
+        assert!(response.saved_components >= 1);
+        let nodes_saved = response.nodes_total - response.nodes_burned;
+        assert_eq!(response.ff_efficiency,
+                   Some(nodes_saved as f64 / response.nodes_defended as f64));
+    }
+
+    #[test]
+    fn test_time_series() {
+        let mut problem = initialize(OSMFStrategy::Greedy(
+            GreedyStrategy::new(TEST_DATA.graph.clone())));
+        problem.simulate();
+
+        let series = problem.time_series_response();
+        let len = problem.global_time as usize + 1;
+        assert_eq!(series.burned_cumulative.len(), len);
+        assert_eq!(series.burned_new.len(), len);
+        assert_eq!(series.burned_new[0], problem.settings.num_roots);
+        assert_eq!(series.burned_cumulative[len - 1], problem.node_data.burning.len());
+        assert_eq!(series.defended_cumulative[len - 1], problem.node_data.defended.len());
+
+        // The time index yields the same results as scanning the node data
+        let index = problem.node_data.time_index.take().unwrap();
+        for time in 0..len as TimeUnit + 5 {
+            assert_eq!(index.burning_by(&time), problem.node_data.count_burning_by(&time));
+            assert_eq!(index.defended_by(&time), problem.node_data.count_defended_by(&time));
+            let mut burning_at = problem.node_data.get_burning_at(&time);
+            burning_at.sort_unstable();
+            assert_eq!(index.burning_at(&time), burning_at);
+        }
+    }
+
+    #[test]
+    fn test_plan_validation() {
+        let settings = OSMFSettings {
+            seed: Some(5),
+            ..TEST_DATA.settings.clone()
+        };
+        let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                           OSMFStrategy::Greedy(GreedyStrategy::new(
+                                               TEST_DATA.graph.clone()))).unwrap();
+        problem.simulate();
+
+        // Replaying the defenses of a simulation yields a valid plan with the same results
+        let roots = problem.node_data.get_roots();
+        let mut defenses: Vec<OSMFPlannedDefense> = Vec::new();
+        for nd in problem.node_data.defended.values() {
+            match defenses.iter_mut().find(|d| d.time == nd.time) {
+                Some(defense) => defense.nodes.push(nd.node_id),
+                None => defenses.push(OSMFPlannedDefense { time: nd.time, nodes: vec![nd.node_id] }),
+            }
+        }
+        // The strategy in the settings is irrelevant for the replay
+        let plan = OSMFDefensePlan { roots: roots.clone(), defenses };
+        let replay_settings = OSMFSettings {
+            strategy_name: "Unknown".to_string(),
+            ..settings.clone()
+        };
+        let mut replay = OSMFProblem::with_plan(TEST_DATA.graph.clone(), replay_settings, plan)
+            .unwrap();
+        assert!(replay.strategy().is_none());
+        replay.simulate();
+        let validation = replay.plan_validation_response();
+        assert!(validation.valid, "violations: {:?}", validation.violations);
+        let results = validation.results.unwrap();
+        assert_eq!(results.nodes_burned, problem.node_data.burning.len());
+        assert_eq!(results.nodes_defended, problem.node_data.defended.len());
+
+        // Violations are reported precisely
+        let free_node = (0..TEST_DATA.graph.num_nodes)
+            .find(|node_id| !roots.contains(node_id))
+            .unwrap();
+        let num_nodes = TEST_DATA.graph.num_nodes;
+        let plan = OSMFDefensePlan {
+            roots: vec![roots[0], num_nodes],
+            defenses: vec![
+                OSMFPlannedDefense { time: 0, nodes: vec![free_node] },
+                OSMFPlannedDefense { time: 5, nodes: vec![free_node] },
+                OSMFPlannedDefense { time: 10, nodes: vec![roots[0], free_node] },
+            ],
+        };
+        let mut replay = OSMFProblem::with_plan(TEST_DATA.graph.clone(), settings, plan).unwrap();
+        replay.simulate();
+        let validation = replay.plan_validation_response();
+        assert!(!validation.valid);
+        assert!(validation.results.is_none());
+        let expected = [
+            OSMFPlanViolation::UnknownNode { node_id: num_nodes },
+            OSMFPlanViolation::InvalidTime { node_id: free_node, time: 0 },
+            OSMFPlanViolation::BudgetExceeded { time: 5, defended: 1, budget: 0 },
+            OSMFPlanViolation::BudgetExceeded { time: 10, defended: 3, budget: 2 },
+            OSMFPlanViolation::NodeBurning { node_id: roots[0], time: 10, burning_since: 0 },
+            OSMFPlanViolation::NodeDefended { node_id: free_node, time: 10, defended_since: 5 },
+        ];
+        assert_eq!(validation.violations, expected);
+    }
+
+    #[test]
+    fn test_exact() {
+        for seed in 1..4 {
+            let settings = OSMFSettings {
+                num_roots: 2,
+                seed: Some(seed),
+                ..TEST_DATA.settings.clone()
+            };
+            let simulate = |strategy_name: &str| {
+                let strategy = OSMFStrategy::from_name_and_graph(strategy_name,
+                                                                 TEST_DATA.graph.clone()).unwrap();
+                let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                                   strategy).unwrap();
+                problem.simulate();
+                problem
+            };
+
+            let exact = simulate("Exact");
+            let nodes_burned = exact.simulation_response().nodes_burned;
+            match exact.strategy() {
+                Some(OSMFStrategy::Exact(strategy)) => {
+                    assert!(strategy.is_optimal());
+                    assert_eq!(strategy.lower_bound(), nodes_burned as f64);
+                }
+                _ => unreachable!(),
+            }
+            for strategy_name in ["Greedy", "Score", "Priority", "MinCut", "Rollout"] {
+                assert!(nodes_burned <= simulate(strategy_name).simulation_response().nodes_burned);
+            }
+        }
+
+        // Larger graphs are rejected
+        let graph = Arc::new(Graph::parse_from_file("data/stgcenter_undirected.fmi").unwrap());
+        let strategy = OSMFStrategy::from_name_and_graph("Exact", graph.clone()).unwrap();
+        assert!(matches!(OSMFProblem::new(graph, TEST_DATA.settings.clone(), strategy),
+                         Err(OSMFSettingsError::InvalidStrategyParameter { .. })));
+    }
+
+    #[test]
+    fn test_strategy_parameters() {
+        let settings = OSMFSettings {
+            strategy_name: "Score".to_string(),
+            seed: Some(1),
+            ..TEST_DATA.settings.clone()
+        };
+        let simulate = |strategy_name: &str, params: &[(&str, &str)]| {
+            let mut settings = settings.clone();
+            settings.strategy_name = strategy_name.to_string();
+            settings.strategy_params = params.iter()
+                .map(|&(name, value)| (name.to_string(), value.parse().unwrap()))
+                .collect();
+            let strategy = OSMFStrategy::from_name_and_graph(strategy_name,
+                                                             TEST_DATA.graph.clone()).unwrap();
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, strategy)?;
+            problem.simulate();
+            Ok::<_, OSMFSettingsError>(problem.simulation_response().nodes_burned)
+        };
+
+        let schema = OSMFStrategy::parameter_schema("Score").unwrap();
+        assert_eq!(schema.len(), 2);
+        assert!(OSMFStrategy::parameter_schema("Random").unwrap().is_empty());
+        assert!(OSMFStrategy::parameter_schema("Unknown").is_none());
+
+        // Explicit default values behave like no values
+        assert_eq!(simulate("Score", &[]).unwrap(),
+                   simulate("Score", &[("distance_weight", "2"), ("importance_weight", "1")]).unwrap());
+        assert_eq!(simulate("Greedy", &[]).unwrap(),
+                   simulate("Greedy", &[("tie_break", "Importance")]).unwrap());
+
+        assert!(simulate("Score", &[("distance_weight", "0"), ("importance_weight", "0")]).is_ok());
+        assert!(simulate("Priority", &[("quantile", "0")]).is_ok());
+        assert!(simulate("Priority", &[("quantile", "1")]).is_ok());
+        assert!(simulate("Greedy", &[("tie_break", "None")]).is_ok());
+
+        assert!(matches!(simulate("Score", &[("distance_weight", "-1")]),
+                         Err(OSMFSettingsError::InvalidStrategyParameter { .. })));
+        assert!(matches!(simulate("Score", &[("quantile", "0.5")]),
+                         Err(OSMFSettingsError::InvalidStrategyParameter { .. })));
+        assert!(matches!(simulate("Greedy", &[("tie_break", "Degree")]),
+                         Err(OSMFSettingsError::InvalidStrategyParameter { .. })));
+    }
+
+    #[test]
+    fn test_min_cut() {
+        // Enough firefighters to defend all neighbors of a single root before the fire spreads
+        let max_degree = (0..TEST_DATA.graph.num_nodes)
+            .map(|node_id| TEST_DATA.graph.get_node_degree(node_id))
+            .max()
+            .unwrap();
+        for seed in 1..4 {
+            let settings = OSMFSettings {
+                strategy_name: "MinCut".to_string(),
+                num_roots: 1,
+                num_ffs: max_degree,
+                strategy_every: 1,
+                seed: Some(seed),
+                ..TEST_DATA.settings.clone()
+            };
+            let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name,
+                                                             TEST_DATA.graph.clone()).unwrap();
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings, strategy).unwrap();
+            problem.simulate();
+            assert_eq!(problem.simulation_response().nodes_burned, 1);
+        }
+
+        for seed in 1..4 {
+            let settings = OSMFSettings {
+                strategy_name: "MinCut".to_string(),
+                seed: Some(seed),
+                strategy_params: [("num_splits".to_string(), "2".parse().unwrap())].into(),
+                ..TEST_DATA.settings.clone()
+            };
+            let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name,
+                                                             TEST_DATA.graph.clone()).unwrap();
+            let mut problem = OSMFProblem::new(TEST_DATA.graph.clone(), settings.clone(),
+                                               strategy).unwrap();
+            problem.simulate();
+            let response = problem.simulation_response();
+            assert!(!problem.is_active);
+            assert!(response.nodes_defended <= settings.firefighters_by(response.end_time));
+            assert!(problem.time_series_response().defended_new.iter()
+                .all(|&num_defended| num_defended <= settings.num_ffs));
+        }
+    }
+
+    #[test]
+    fn test_rollout() {
+        for seed in 1..4 {
+            // With a single candidate, the base policy decides alone
+            let greedy = simulate_strategy("Greedy", seed, &[]);
+            let rollout = simulate_strategy("Rollout", seed, &[("num_candidates", "1")]);
+            assert_eq!(rollout.node_data.get_defended_at(&10), greedy.node_data.get_defended_at(&10));
+            assert_eq!(rollout.simulation_response().nodes_burned,
+                       greedy.simulation_response().nodes_burned);
+
+            let rollout = simulate_strategy("Rollout", seed,
+                                            &[("horizon", "2"), ("base_policy", "Score")]);
+            assert_within_budget(&rollout);
+        }
+    }
+
+    #[test]
+    fn test_annealing() {
+        let graph = Arc::new(Graph::parse_from_file("data/stgcenter_undirected.fmi").unwrap());
+        let mut total_burned = [0, 0];
+        for seed in 1..4 {
+            let simulate = |strategy_name: &str| {
+                let settings = OSMFSettings {
+                    graph_name: "stgcenter".to_string(),
+                    strategy_name: strategy_name.to_string(),
+                    num_roots: 3,
+                    num_ffs: 2,
+                    strategy_every: 10,
+                    seed: Some(seed),
+                    strategy_params: [("max_evaluations", "500")]
+                        .iter()
+                        .filter(|_| strategy_name == "Annealing")
+                        .map(|&(name, value)| (name.to_string(), value.parse().unwrap()))
+                        .collect(),
+                    ..OSMFSettings::default()
+                };
+                let strategy = OSMFStrategy::from_name_and_graph(strategy_name, graph.clone())
+                    .unwrap();
+                let mut problem = OSMFProblem::new(graph.clone(), settings, strategy).unwrap();
+                problem.simulate();
+                problem
+            };
+
+            let single = simulate("SingleMinDistanceSet");
+            let annealing = simulate("Annealing");
+            let burned = annealing.simulation_response().nodes_burned;
+            assert!(burned <= single.simulation_response().nodes_burned);
+            total_burned[0] += single.simulation_response().nodes_burned;
+            total_burned[1] += burned;
+
+            // The search is reproducible and its plan is a valid static plan
+            let again = simulate("Annealing");
+            assert_eq!(again.simulation_response().nodes_burned, burned);
+            let plan = match annealing.strategy() {
+                Some(OSMFStrategy::Annealing(strategy)) => strategy.defense_plan(),
+                _ => unreachable!(),
+            };
+            let mut replay = OSMFProblem::with_plan(graph.clone(), annealing.settings.clone(),
+                                                    plan).unwrap();
+            replay.simulate();
+            let validation = replay.plan_validation_response();
+            assert!(validation.valid, "violations: {:?}", validation.violations);
+            assert_eq!(validation.results.unwrap().nodes_burned, burned);
+        }
+        assert!(total_burned[1] < total_burned[0]);
+    }
+
+    #[test]
+    fn test_dominator() {
+        let graph = &TEST_DATA.graph;
+        let round = TEST_DATA.settings.strategy_every;
+        for seed in 1..6 {
+            let problem = simulate_strategy("Dominator", seed, &[]);
+            assert_within_budget(&problem);
+
+            // The first round defends the node that dominates the most nodes per distance to the
+            // fire, and none of the defended nodes dominates another one
+            let roots = problem.node_data.get_roots();
+            let (idoms, order) = graph.dominator_tree(&roots, |_| false);
+            let mut num_dominated = vec![0.0; graph.num_nodes];
+            for &node_id in order.iter().rev() {
+                num_dominated[node_id] += 1.0;
+                if idoms[node_id] < usize::MAX {
+                    num_dominated[idoms[node_id]] += num_dominated[node_id];
+                }
+            }
+            let burning = burning_before(&problem, round);
+            let dists = graph.run_dijkstra(&burning);
+            let rank = |node_id: usize| num_dominated[node_id] / dists[node_id].max(1) as f64;
+            let max_rank = order.iter()
+                .filter(|node_id| !burning.contains(node_id) && dists[**node_id] < usize::MAX)
+                .map(|&node_id| rank(node_id))
+                .fold(0.0, f64::max);
+
+            let defended = problem.node_data.get_defended_at(&round);
+            assert!(!defended.is_empty());
+            assert_eq!(defended.iter().map(|&node_id| rank(node_id)).fold(0.0, f64::max), max_rank);
+            for &node_id in &defended {
+                let mut dominator = idoms[node_id];
+                while dominator < usize::MAX {
+                    assert!(!defended.contains(&dominator));
+                    dominator = idoms[dominator];
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree() {
+        for seed in 1..6 {
+            assert_within_budget(&simulate_strategy("Tree", seed, &[]));
+        }
+
+        // Ternary tree that is small enough for the exact dynamic program to be used and to
+        // finish within its state limit
+        let num_nodes = 20;
+        let nodes: String = (0..num_nodes)
+            .map(|i| format!("{} {} 48.{:04} 9.{:04} 0\n", i, 1000 + i, i, i))
+            .collect();
+        let mut edges: Vec<_> = (1..num_nodes)
+            .flat_map(|i| [((i - 1) / 3, i, 1 + i % 3), (i, (i - 1) / 3, 1 + i % 3)])
+            .collect();
+        edges.sort_unstable();
+        let edges: String = edges.iter()
+            .map(|(src, tgt, dist)| format!("{} {} {} 5 80\n", src, tgt, dist))
+            .collect();
+        let path = std::env::temp_dir().join("osmff_test_tree.fmi");
+        std::fs::write(&path, format!("# Ternary tree\n\n{}\n{}\n{}{}", num_nodes,
+                                      2 * (num_nodes - 1), nodes, edges)).unwrap();
+        let graph = Arc::new(Graph::parse_from_file(path.to_str().unwrap()).unwrap());
+
+        let round = 1;
+        for seed in 1..6 {
+            let simulate = |params: &[(&str, &str)], max_time: Option<TimeUnit>| {
+                let mut settings = OSMFSettings {
+                    graph_name: "tree".to_string(),
+                    strategy_name: "Tree".to_string(),
+                    num_roots: 1,
+                    num_ffs: 1,
+                    strategy_every: round,
+                    seed: Some(seed),
+                    strategy_params: params.iter()
+                        .map(|&(name, value)| (name.to_string(), value.parse().unwrap()))
+                        .collect(),
+                    ..OSMFSettings::default()
+                };
+                settings.termination.max_time = max_time;
+                let strategy = OSMFStrategy::from_name_and_graph("Tree", graph.clone()).unwrap();
+                let mut problem = OSMFProblem::new(graph.clone(), settings, strategy).unwrap();
+                problem.simulate();
+                problem
+            };
+            let greedy = simulate(&[], None);
+            let exact = simulate(&[("algorithm", "Exact"), ("max_exact_nodes", "64")], None);
+            assert_within_budget(&greedy);
+            assert_within_budget(&exact);
+
+            // The exact dynamic program never saves fewer nodes than the greedy algorithm
+            assert!(exact.simulation_response().nodes_burned
+                <= greedy.simulation_response().nodes_burned);
+
+            // The first round defends the nodes chosen by the exact dynamic program
+            let before = simulate(&[], Some(round - 1));
+            let tree = FireTree::shortest_path_tree(&graph, &before.settings, &before.node_data,
+                                                    round);
+            assert!(tree.len() <= 64);
+            let rounds: Vec<_> = (round..=tree.max_arrival().unwrap().max(round))
+                .map(|time| (time, before.settings.firefighters_at(time)))
+                .filter(|&(_, num_ffs)| num_ffs > 0)
+                .collect();
+            let plan = tree.optimal(&rounds).unwrap();
+            assert_eq!(exact.node_data.get_defended_at(&round), plan[0]);
+        }
+    }
+
+    #[test]
+    fn test_centrality() {
+        let graph = &TEST_DATA.graph;
+        let round = TEST_DATA.settings.strategy_every;
+        let centralities = [
+            ("Betweenness", Centrality::Betweenness),
+            ("SampledBetweenness", Centrality::default()),
+            ("Closeness", Centrality::Closeness),
+            ("PageRank", Centrality::PageRank),
+        ];
+        for seed in 1..6 {
+            for (name, centrality) in centralities {
+                let problem = simulate_strategy("Centrality", seed, &[("centrality", name)]);
+                assert_within_budget(&problem);
+
+                // The first round defends the nodes with the highest centrality per squared
+                // distance to the fire
+                let centralities = graph.centrality(centrality);
+                let burning = burning_before(&problem, round);
+                let dists = graph.run_dijkstra(&burning);
+                let score = |node_id: usize| centralities[node_id]
+                    / (dists[node_id].max(1) as f64).powi(2);
+                let defended = problem.node_data.get_defended_at(&round);
+                assert_eq!(defended.len(), TEST_DATA.settings.firefighters_at(round));
+                let min_defended = defended.iter()
+                    .map(|&node_id| score(node_id))
+                    .fold(f64::INFINITY, f64::min);
+                assert!((0..graph.num_nodes)
+                    .filter(|node_id| !burning.contains(node_id) && !defended.contains(node_id))
+                    .filter(|&node_id| dists[node_id] < usize::MAX)
+                    .all(|node_id| score(node_id) <= min_defended));
+            }
+        }
+    }
 }
\ No newline at end of file
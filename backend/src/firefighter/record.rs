@@ -0,0 +1,469 @@
+use std::convert::{TryFrom, TryInto};
+
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::firefighter::problem::{OSMFEndReason, OSMFSettings, OSMFSettingsError};
+use crate::firefighter::TimeUnit;
+use crate::graph::Graph;
+
+/// Magic bytes at the start of a binary simulation record
+const MAGIC: &[u8; 4] = b"OSMF";
+/// Current version of the simulation record format
+pub const RECORD_VERSION: u64 = 1;
+
+#[derive(Debug, Display, Error)]
+pub enum OSMFRecordError {
+    #[display(fmt = "Invalid simulation record: {}", reason)]
+    InvalidRecord { reason: String },
+    #[display(fmt = "Simulation record does not match graph {}: expected content hash {:016x}, \
+    found {:016x}", graph_name, expected_hash, actual_hash)]
+    GraphMismatch { graph_name: String, expected_hash: u64, actual_hash: u64 },
+    #[display(fmt = "Invalid settings in simulation record: {}", source)]
+    InvalidSettings { source: OSMFSettingsError },
+}
+
+impl From<OSMFSettingsError> for OSMFRecordError {
+    fn from(err: OSMFSettingsError) -> Self {
+        Self::InvalidSettings {
+            source: err,
+        }
+    }
+}
+
+/// Identity of the graph that a simulation was run on
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OSMFGraphIdentity {
+    pub name: String,
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub content_hash: u64,
+}
+
+impl OSMFGraphIdentity {
+    /// Get the identity of `graph` with name `name`
+    pub fn of(name: &str, graph: &Graph) -> Self {
+        Self {
+            name: name.to_string(),
+            num_nodes: graph.num_nodes,
+            num_edges: graph.num_edges,
+            content_hash: graph.content_hash(),
+        }
+    }
+}
+
+/// Complete result of a firefighter simulation that can be exported and imported again
+/// without re-running the simulation
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OSMFSimulationRecord {
+    pub version: u64,
+    pub settings: OSMFSettings,
+    pub seed: u64,
+    pub graph: OSMFGraphIdentity,
+    pub roots: Vec<usize>,
+    pub late_ignitions: Vec<usize>,
+    /// Burned nodes and the times at which they caught fire
+    pub burned: Vec<(usize, TimeUnit)>,
+    /// Defended nodes and the times at which they were defended
+    pub defended: Vec<(usize, TimeUnit)>,
+    /// Protected edges given by source and target and the times at which they were protected
+    pub protected: Vec<(usize, usize, TimeUnit)>,
+    pub fire_front: Vec<usize>,
+    pub end_time: TimeUnit,
+    pub end_reason: Option<OSMFEndReason>,
+    pub simulation_time_millis: u128,
+}
+
+impl OSMFSimulationRecord {
+    /// Serialize this record to JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize simulation record")
+    }
+
+    /// Deserialize a record from JSON
+    pub fn from_json(data: &[u8]) -> Result<Self, OSMFRecordError> {
+        let record: Self = serde_json::from_slice(data)
+            .map_err(|err| OSMFRecordError::InvalidRecord { reason: err.to_string() })?;
+        record.check_version()?;
+        Ok(record)
+    }
+
+    /// Serialize this record to a compact binary form.
+    /// Node ids are delta encoded in ascending order and all integers are encoded as
+    /// variable-length integers. The settings are embedded as JSON.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        put_varint(&mut buf, self.version);
+
+        let settings = serde_json::to_vec(&self.settings).expect("Failed to serialize settings");
+        put_bytes(&mut buf, &settings);
+        put_varint(&mut buf, self.seed);
+
+        put_bytes(&mut buf, self.graph.name.as_bytes());
+        put_varint(&mut buf, self.graph.num_nodes as u64);
+        put_varint(&mut buf, self.graph.num_edges as u64);
+        buf.extend_from_slice(&self.graph.content_hash.to_le_bytes());
+
+        put_node_ids(&mut buf, &self.roots);
+        put_node_ids(&mut buf, &self.late_ignitions);
+        put_node_times(&mut buf, &self.burned);
+        put_node_times(&mut buf, &self.defended);
+
+        let mut protected = self.protected.clone();
+        protected.sort_unstable();
+        put_varint(&mut buf, protected.len() as u64);
+        let mut prev_src = 0;
+        for (src, tgt, time) in protected {
+            put_varint(&mut buf, (src - prev_src) as u64);
+            put_varint(&mut buf, tgt as u64);
+            put_varint(&mut buf, time);
+            prev_src = src;
+        }
+
+        put_varint(&mut buf, self.fire_front.len() as u64);
+        for &size in &self.fire_front {
+            put_varint(&mut buf, size as u64);
+        }
+
+        put_varint(&mut buf, self.end_time);
+        put_varint(&mut buf, match self.end_reason {
+            None => 0,
+            Some(OSMFEndReason::FireStopped) => 1,
+            Some(OSMFEndReason::Contained) => 2,
+            Some(OSMFEndReason::MaxTime) => 3,
+            Some(OSMFEndReason::MaxMillis) => 4,
+            Some(OSMFEndReason::MaxStrategyRounds) => 5,
+        });
+        put_varint(&mut buf, self.simulation_time_millis.min(u64::MAX as u128) as u64);
+
+        buf
+    }
+
+    /// Deserialize a record from its compact binary form
+    pub fn from_binary(data: &[u8]) -> Result<Self, OSMFRecordError> {
+        if !data.starts_with(MAGIC) {
+            return Err(OSMFRecordError::InvalidRecord {
+                reason: "missing magic bytes".to_string(),
+            });
+        }
+        let mut reader = Reader { data, pos: MAGIC.len() };
+
+        let version = reader.varint()?;
+        if version != RECORD_VERSION {
+            return Err(unsupported_version(version));
+        }
+
+        let settings = serde_json::from_slice(reader.bytes()?)
+            .map_err(|err| OSMFRecordError::InvalidRecord { reason: err.to_string() })?;
+        let seed = reader.varint()?;
+
+        let graph = OSMFGraphIdentity {
+            name: String::from_utf8(reader.bytes()?.to_vec())
+                .map_err(|err| OSMFRecordError::InvalidRecord { reason: err.to_string() })?,
+            num_nodes: reader.usize()?,
+            num_edges: reader.usize()?,
+            content_hash: u64::from_le_bytes(reader.take(8)?.try_into().unwrap()),
+        };
+
+        let roots = reader.node_ids()?;
+        let late_ignitions = reader.node_ids()?;
+        let burned = reader.node_times()?;
+        let defended = reader.node_times()?;
+
+        let num_protected = reader.len()?;
+        let mut protected = Vec::with_capacity(num_protected);
+        let mut prev_src = 0;
+        for _ in 0..num_protected {
+            let src = reader.delta(prev_src)?;
+            protected.push((src, reader.usize()?, reader.varint()?));
+            prev_src = src;
+        }
+
+        let num_fire_front = reader.len()?;
+        let fire_front = (0..num_fire_front)
+            .map(|_| reader.usize())
+            .collect::<Result<_, _>>()?;
+
+        let end_time = reader.varint()?;
+        let end_reason = match reader.varint()? {
+            0 => None,
+            1 => Some(OSMFEndReason::FireStopped),
+            2 => Some(OSMFEndReason::Contained),
+            3 => Some(OSMFEndReason::MaxTime),
+            4 => Some(OSMFEndReason::MaxMillis),
+            5 => Some(OSMFEndReason::MaxStrategyRounds),
+            tag => return Err(OSMFRecordError::InvalidRecord {
+                reason: format!("unknown end reason: {}", tag),
+            }),
+        };
+        let simulation_time_millis = reader.varint()? as u128;
+
+        if reader.pos != data.len() {
+            return Err(OSMFRecordError::InvalidRecord {
+                reason: format!("{} trailing bytes", data.len() - reader.pos),
+            });
+        }
+
+        Ok(Self {
+            version,
+            settings,
+            seed,
+            graph,
+            roots,
+            late_ignitions,
+            burned,
+            defended,
+            protected,
+            fire_front,
+            end_time,
+            end_reason,
+            simulation_time_millis,
+        })
+    }
+
+    /// Check whether this record has the current format version
+    fn check_version(&self) -> Result<(), OSMFRecordError> {
+        if self.version != RECORD_VERSION {
+            return Err(unsupported_version(self.version));
+        }
+        Ok(())
+    }
+}
+
+fn unsupported_version(version: u64) -> OSMFRecordError {
+    OSMFRecordError::InvalidRecord {
+        reason: format!("unsupported version {}, expected {}", version, RECORD_VERSION),
+    }
+}
+
+/// Append `value` as LEB128 variable-length integer to `buf`
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Append `bytes` prefixed with their length to `buf`
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+/// Append the node ids in `node_ids` in ascending order and delta encoded to `buf`
+fn put_node_ids(buf: &mut Vec<u8>, node_ids: &[usize]) {
+    let mut node_ids = node_ids.to_vec();
+    node_ids.sort_unstable();
+    put_varint(buf, node_ids.len() as u64);
+    let mut prev = 0;
+    for node_id in node_ids {
+        put_varint(buf, (node_id - prev) as u64);
+        prev = node_id;
+    }
+}
+
+/// Append the node ids in `node_times` in ascending order and delta encoded together with
+/// their times to `buf`
+fn put_node_times(buf: &mut Vec<u8>, node_times: &[(usize, TimeUnit)]) {
+    let mut node_times = node_times.to_vec();
+    node_times.sort_unstable();
+    put_varint(buf, node_times.len() as u64);
+    let mut prev = 0;
+    for (node_id, time) in node_times {
+        put_varint(buf, (node_id - prev) as u64);
+        put_varint(buf, time);
+        prev = node_id;
+    }
+}
+
+/// Reader for the binary form of a simulation record
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Take the next `n` bytes
+    fn take(&mut self, n: usize) -> Result<&'a [u8], OSMFRecordError> {
+        if self.data.len() - self.pos < n {
+            return Err(OSMFRecordError::InvalidRecord {
+                reason: "unexpected end of data".to_string(),
+            });
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    /// Read a LEB128 variable-length integer
+    fn varint(&mut self) -> Result<u64, OSMFRecordError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(OSMFRecordError::InvalidRecord {
+            reason: "variable-length integer is too long".to_string(),
+        })
+    }
+
+    /// Read a variable-length integer that fits into a `usize`
+    fn usize(&mut self) -> Result<usize, OSMFRecordError> {
+        usize::try_from(self.varint()?)
+            .map_err(|err| OSMFRecordError::InvalidRecord { reason: err.to_string() })
+    }
+
+    /// Read the length of a sequence. Each element of the sequence takes at least one byte.
+    fn len(&mut self) -> Result<usize, OSMFRecordError> {
+        let len = self.usize()?;
+        if len > self.data.len() - self.pos {
+            return Err(OSMFRecordError::InvalidRecord {
+                reason: format!("sequence length {} exceeds the data", len),
+            });
+        }
+        Ok(len)
+    }
+
+    /// Read length prefixed bytes
+    fn bytes(&mut self) -> Result<&'a [u8], OSMFRecordError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    /// Read a node id that is delta encoded relative to `prev`
+    fn delta(&mut self, prev: usize) -> Result<usize, OSMFRecordError> {
+        prev.checked_add(self.usize()?)
+            .ok_or_else(|| OSMFRecordError::InvalidRecord {
+                reason: "node id overflow".to_string(),
+            })
+    }
+
+    /// Read delta encoded node ids
+    fn node_ids(&mut self) -> Result<Vec<usize>, OSMFRecordError> {
+        let len = self.len()?;
+        let mut node_ids = Vec::with_capacity(len);
+        let mut prev = 0;
+        for _ in 0..len {
+            prev = self.delta(prev)?;
+            node_ids.push(prev);
+        }
+        Ok(node_ids)
+    }
+
+    /// Read delta encoded node ids together with their times
+    fn node_times(&mut self) -> Result<Vec<(usize, TimeUnit)>, OSMFRecordError> {
+        let len = self.len()?;
+        let mut node_times = Vec::with_capacity(len);
+        let mut prev = 0;
+        for _ in 0..len {
+            prev = self.delta(prev)?;
+            node_times.push((prev, self.varint()?));
+        }
+        Ok(node_times)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::firefighter::problem::{OSMFProblem, OSMFSettings};
+    use crate::firefighter::record::OSMFSimulationRecord;
+    use crate::firefighter::strategy::OSMFStrategy;
+    use crate::graph::Graph;
+
+    fn simulated_problem(graph: &Arc<Graph>) -> OSMFProblem {
+        let settings = OSMFSettings {
+            graph_name: "bbgrund".to_string(),
+            strategy_name: "Greedy".to_string(),
+            num_roots: 5,
+            num_ffs: 2,
+            strategy_every: 10,
+            seed: Some(11),
+            ..OSMFSettings::default()
+        };
+        let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name,
+                                                         graph.clone()).unwrap();
+        let mut problem = OSMFProblem::new(graph.clone(), settings, strategy).unwrap();
+        problem.simulate();
+        problem
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let graph = Arc::new(Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap());
+        let problem = simulated_problem(&graph);
+        let record = problem.to_record();
+        let json = record.to_json();
+
+        let from_json = OSMFSimulationRecord::from_json(json.as_bytes()).unwrap();
+        assert_eq!(from_json.to_json(), json);
+
+        let binary = record.to_binary();
+        assert!(binary.len() < json.len());
+        let from_binary = OSMFSimulationRecord::from_binary(&binary).unwrap();
+        assert_eq!(from_binary.to_json(), json);
+
+        // An imported simulation yields the same results
+        let imported = OSMFProblem::from_record(graph, from_binary).unwrap();
+        let expected = serde_json::to_value(problem.simulation_response()).unwrap();
+        let actual = serde_json::to_value(imported.simulation_response()).unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(serde_json::to_value(imported.time_series_response()).unwrap(),
+                   serde_json::to_value(problem.time_series_response()).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_record() {
+        let graph = Arc::new(Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap());
+        let record = simulated_problem(&graph).to_record();
+
+        let binary = record.to_binary();
+        assert!(OSMFSimulationRecord::from_binary(&binary[..binary.len() - 1]).is_err());
+        assert!(OSMFSimulationRecord::from_binary(&binary[1..]).is_err());
+        assert!(OSMFSimulationRecord::from_json(b"{}").is_err());
+
+        let other_graph = Arc::new(Graph::parse_from_file("data/stgcenter_undirected.fmi").unwrap());
+        assert!(OSMFProblem::from_record(other_graph, record.clone()).is_err());
+
+        let mut invalid = record.clone();
+        invalid.burned.push((graph.num_nodes, 0));
+        assert!(OSMFProblem::from_record(graph.clone(), invalid).is_err());
+
+        // The end time must match the fire front
+        for end_time in [u64::MAX, 1 << 40] {
+            let mut invalid = record.clone();
+            invalid.end_time = end_time;
+            assert!(OSMFProblem::from_record(graph.clone(), invalid).is_err());
+        }
+        let mut invalid = record.clone();
+        invalid.fire_front.push(0);
+        assert!(OSMFProblem::from_record(graph.clone(), invalid).is_err());
+        let mut invalid = record.clone();
+        invalid.end_time += 1;
+        assert!(OSMFProblem::from_record(graph.clone(), invalid).is_err());
+
+        // Nodes cannot burn and be defended
+        let mut invalid = record.clone();
+        let (node_id, time) = invalid.burned[0];
+        invalid.defended.push((node_id, time));
+        assert!(OSMFProblem::from_record(graph.clone(), invalid).is_err());
+
+        // Simulations may end long after the last event, and neither their strategy nor the
+        // plan they replayed is needed to import them
+        let mut valid = record.clone();
+        valid.end_time += 5000;
+        valid.fire_front.resize(valid.end_time as usize + 1, 0);
+        valid.settings.strategy_name = "Unknown".to_string();
+        let imported = OSMFProblem::from_record(graph.clone(), valid).unwrap();
+        assert!(imported.strategy().is_none());
+        assert_eq!(imported.time_series_response().burned_cumulative.len(),
+                   record.end_time as usize + 5001);
+
+        assert!(OSMFProblem::from_record(graph, record).is_ok());
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+
+use crate::firefighter::parameters::{OSMFParameters, OSMFParameterSpec};
+use crate::firefighter::problem::{NodeDataStorage, OSMFSettings, OSMFSettingsError};
+use crate::firefighter::strategy::OSMFStrategy;
+use crate::firefighter::TimeUnit;
+use crate::graph::Graph;
+
+/// Factory that creates a new instance of a custom strategy that operates on the given graph
+pub type OSMFStrategyFactory = Box<dyn Fn(Arc<Graph>) -> Box<dyn OSMFCustomStrategy> + Send + Sync>;
+
+/// Registered custom strategy
+struct RegisteredStrategy {
+    parameters: Vec<OSMFParameterSpec>,
+    factory: OSMFStrategyFactory,
+}
+
+/// Registry of all custom strategies by name.
+/// The registry only lives as long as the process. The `osmff_service` and `bench` binaries of
+/// this crate do not register any strategies, so custom strategies are only available in
+/// binaries that register them before they list strategies or start simulations.
+static REGISTRY: Lazy<RwLock<BTreeMap<String, RegisteredStrategy>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// Fire containment strategy that is implemented outside of this crate.
+/// Custom strategies defend nodes and are notified about the state of the simulation through a
+/// read-only `OSMFStateView`.
+pub trait OSMFCustomStrategy: Debug + Send {
+    /// Apply the validated values of the parameters declared at registration.
+    /// Called once before the simulation starts.
+    fn configure(&mut self, _params: &OSMFParameters) {}
+
+    /// Initialize the strategy after the fire roots caught fire at time `0`
+    fn initialize(&mut self, _state: &OSMFStateView) {}
+
+    /// Notify the strategy that the nodes in `ignited` caught fire after time `0`
+    fn notify_ignitions(&mut self, _ignited: &[usize], _state: &OSMFStateView) {}
+
+    /// Select the nodes to defend in the current strategy round.
+    /// Only the first `state.budget()` nodes that exist and are undefended are defended.
+    fn select_nodes(&mut self, state: &OSMFStateView) -> Vec<usize>;
+}
+
+/// Read-only view of the state of a firefighter simulation at a specific time
+pub struct OSMFStateView<'a> {
+    graph: &'a Graph,
+    settings: &'a OSMFSettings,
+    node_data: &'a NodeDataStorage,
+    time: TimeUnit,
+}
+
+impl<'a> OSMFStateView<'a> {
+    /// Create a new view of the state in `node_data` at time `time`
+    pub(super) fn new(graph: &'a Graph, settings: &'a OSMFSettings, node_data: &'a NodeDataStorage,
+                      time: TimeUnit) -> Self {
+        Self {
+            graph,
+            settings,
+            node_data,
+            time,
+        }
+    }
+
+    /// Get the graph the fire spreads on
+    pub fn graph(&self) -> &Graph {
+        self.graph
+    }
+
+    /// Get the settings of the simulation
+    pub fn settings(&self) -> &OSMFSettings {
+        self.settings
+    }
+
+    /// Get the current time of the simulation
+    pub fn time(&self) -> TimeUnit {
+        self.time
+    }
+
+    /// Get the number of nodes that can be defended at the current time
+    pub fn budget(&self) -> usize {
+        self.settings.firefighters_at(self.time)
+    }
+
+    /// Is node with id `node_id` burning?
+    pub fn is_burning(&self, node_id: usize) -> bool {
+        self.node_data.is_burning(&node_id)
+    }
+
+    /// Get the time at which the node with id `node_id` caught fire
+    pub fn burning_since(&self, node_id: usize) -> Option<TimeUnit> {
+        self.node_data.get_burning_time(&node_id)
+    }
+
+    /// Is node with id `node_id` burned out, i.e. does it not spread the fire anymore?
+    pub fn is_burned_out(&self, node_id: usize) -> bool {
+        self.node_data.is_burned_out_by(&node_id, &self.time)
+    }
+
+    /// Is node with id `node_id` defended? This includes firebreaks.
+    pub fn is_defended(&self, node_id: usize) -> bool {
+        self.node_data.is_defended(&node_id)
+    }
+
+    /// Is node with id `node_id` neither burning nor defended?
+    pub fn is_undefended(&self, node_id: usize) -> bool {
+        self.node_data.is_undefended(&node_id)
+    }
+
+    /// Is node with id `node_id` a fire root?
+    pub fn is_root(&self, node_id: usize) -> bool {
+        self.node_data.is_root(&node_id)
+    }
+
+    /// Is node with id `node_id` a firebreak?
+    pub fn is_firebreak(&self, node_id: usize) -> bool {
+        self.node_data.is_firebreak(&node_id)
+    }
+
+    /// Get the ids of all burning nodes, including burned out nodes
+    pub fn burning_nodes(&self) -> Vec<usize> {
+        self.node_data.get_burning()
+    }
+
+    /// Get the ids of all burning nodes that still spread the fire
+    pub fn active_fire(&self) -> Vec<usize> {
+        self.node_data.get_burning().into_iter()
+            .filter(|node_id| !self.node_data.is_burned_out_by(node_id, &self.time))
+            .collect()
+    }
+
+    /// Get the ids of all fire roots
+    pub fn roots(&self) -> Vec<usize> {
+        self.node_data.get_roots()
+    }
+}
+
+/// Register the custom strategy `name` that is created by `factory`.
+/// Registered strategies can be selected by name like the built-in strategies in the process
+/// that registered them, see `REGISTRY`.
+/// Fails if a built-in or registered strategy with the same name exists.
+pub fn register_strategy<F>(name: &str, factory: F) -> Result<(), OSMFSettingsError>
+    where F: Fn(Arc<Graph>) -> Box<dyn OSMFCustomStrategy> + Send + Sync + 'static {
+    register_strategy_with_parameters(name, Vec::new(), factory)
+}
+
+/// Register the custom strategy `name` with the tunable parameters `parameters`.
+/// See `register_strategy`.
+pub fn register_strategy_with_parameters<F>(name: &str, parameters: Vec<OSMFParameterSpec>,
+                                            factory: F) -> Result<(), OSMFSettingsError>
+    where F: Fn(Arc<Graph>) -> Box<dyn OSMFCustomStrategy> + Send + Sync + 'static {
+    let mut registry = REGISTRY.write().unwrap();
+    if OSMFStrategy::is_built_in(name) || registry.contains_key(name) {
+        return Err(OSMFSettingsError::DuplicateStrategy {
+            strategy_name: name.to_string(),
+        });
+    }
+    registry.insert(name.to_string(), RegisteredStrategy {
+        parameters,
+        factory: Box::new(factory),
+    });
+
+    log::info!("Registered custom strategy {}", name);
+
+    Ok(())
+}
+
+/// Returns the names of all registered custom strategies
+pub fn registered_strategies() -> Vec<String> {
+    REGISTRY.read().unwrap().keys().cloned().collect()
+}
+
+/// Returns the tunable parameters of the registered custom strategy `name`
+pub(super) fn registered_parameters(name: &str) -> Option<Vec<OSMFParameterSpec>> {
+    REGISTRY.read().unwrap().get(name)
+        .map(|strategy| strategy.parameters.clone())
+}
+
+/// Create a new instance of the registered custom strategy `name` that operates on `graph`
+pub(super) fn create_strategy(name: &str, graph: Arc<Graph>) -> Option<CustomStrategy> {
+    let registry = REGISTRY.read().unwrap();
+    let strategy = registry.get(name)?;
+    Some(CustomStrategy {
+        name: name.to_string(),
+        inner: (strategy.factory)(graph.clone()),
+        graph,
+    })
+}
+
+/// Instance of a registered custom strategy
+#[derive(Debug)]
+pub struct CustomStrategy {
+    name: String,
+    inner: Box<dyn OSMFCustomStrategy>,
+    graph: Arc<Graph>,
+}
+
+impl CustomStrategy {
+    /// Get the name under which this strategy was registered
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(super) fn configure(&mut self, params: &OSMFParameters) {
+        self.inner.configure(params);
+    }
+
+    pub(super) fn initialize(&mut self, settings: &OSMFSettings, node_data: &NodeDataStorage) {
+        let state = OSMFStateView::new(&self.graph, settings, node_data, 0);
+        self.inner.initialize(&state);
+    }
+
+    pub(super) fn notify_ignitions(&mut self, ignited: &[usize], settings: &OSMFSettings,
+                                   node_data: &NodeDataStorage, global_time: TimeUnit) {
+        let state = OSMFStateView::new(&self.graph, settings, node_data, global_time);
+        self.inner.notify_ignitions(ignited, &state);
+    }
+
+    /// Defend the valid nodes selected by the custom strategy within the budget
+    pub(super) fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage,
+                          global_time: TimeUnit) {
+        let state = OSMFStateView::new(&self.graph, settings, node_data, global_time);
+        let mut to_defend = Vec::new();
+        for node_id in self.inner.select_nodes(&state) {
+            if to_defend.len() == state.budget() {
+                break;
+            }
+            if node_id < self.graph.num_nodes && state.is_undefended(node_id)
+                && !to_defend.contains(&node_id) {
+                to_defend.push(node_id);
+            }
+        }
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::firefighter::problem::{OSMFProblem, OSMFSettings};
+    use crate::firefighter::registry::{OSMFCustomStrategy, OSMFStateView, register_strategy};
+    use crate::firefighter::strategy::OSMFStrategy;
+    use crate::graph::Graph;
+
+    /// Strategy that defends the undefended nodes with the lowest ids
+    #[derive(Debug)]
+    struct LowestIdStrategy {
+        num_initialized: usize,
+    }
+
+    impl OSMFCustomStrategy for LowestIdStrategy {
+        fn initialize(&mut self, state: &OSMFStateView) {
+            assert_eq!(state.time(), 0);
+            assert!(!state.roots().is_empty());
+            self.num_initialized += 1;
+        }
+
+        fn select_nodes(&mut self, state: &OSMFStateView) -> Vec<usize> {
+            assert_eq!(self.num_initialized, 1);
+            // Invalid and duplicate nodes are ignored
+            let mut nodes = vec![state.graph().num_nodes, 0, 0];
+            nodes.extend((0..state.graph().num_nodes)
+                .filter(|&node_id| state.is_undefended(node_id)));
+            nodes
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        register_strategy("LowestId", |_| Box::new(LowestIdStrategy { num_initialized: 0 }))
+            .unwrap();
+        assert!(register_strategy("LowestId", |_| Box::new(LowestIdStrategy {
+            num_initialized: 0
+        })).is_err());
+        assert!(register_strategy("Greedy", |_| Box::new(LowestIdStrategy {
+            num_initialized: 0
+        })).is_err());
+        assert!(OSMFStrategy::available_strategies().contains(&"LowestId".to_string()));
+        assert!(!OSMFStrategy::available_strategies().contains(&"Custom".to_string()));
+
+        let graph = Arc::new(Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap());
+        let settings = OSMFSettings {
+            graph_name: "bbgrund".to_string(),
+            strategy_name: "LowestId".to_string(),
+            num_roots: 3,
+            num_ffs: 2,
+            strategy_every: 10,
+            seed: Some(2),
+            ..OSMFSettings::default()
+        };
+        let strategy = OSMFStrategy::from_name_and_graph(&settings.strategy_name, graph.clone())
+            .unwrap();
+        let mut problem = OSMFProblem::new(graph, settings, strategy).unwrap();
+        problem.simulate();
+
+        let response = problem.simulation_response();
+        let budget = problem.simulation_response().end_time as usize / 10 * 2;
+        assert!(response.nodes_defended > 0 && response.nodes_defended <= budget);
+        let time_series = problem.time_series_response();
+        assert!(time_series.defended_new.iter().all(|&num_defended| num_defended <= 2));
+    }
+}
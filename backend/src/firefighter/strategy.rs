@@ -2,59 +2,174 @@ use std::cmp::min;
 use std::collections::{BTreeMap, HashMap, VecDeque, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Instant;
 
 use rand::prelude::*;
 use rand::seq::SliceRandom;
 
 use strum::VariantNames;
-use strum_macros::{EnumString, EnumVariantNames};
-
-use crate::firefighter::problem::{NodeDataStorage, OSMFSettings};
+use strum_macros::{EnumString, EnumVariantNames, IntoStaticStr};
+
+use crate::centrality::{Centrality, DEFAULT_BETWEENNESS_SAMPLES, EXACT_CENTRALITY_NODE_LIMIT};
+use crate::firefighter::distances::IncrementalDistances;
+use crate::firefighter::parameters::{OSMFParameters, OSMFParameterSpec, OSMFParameterType,
+                                     OSMFParameterValue, OSMFStrategyInfo};
+use crate::firefighter::problem::{NodeDataStorage, OSMFDefenseMode, OSMFDefensePlan,
+                                  OSMFFirebreakStrategy, OSMFObjective, OSMFPlannedDefense,
+                                  OSMFSettings, OSMFSettingsError, StepEngine};
+use crate::firefighter::registry::{self, CustomStrategy};
+use crate::firefighter::tree::FireTree;
 use crate::firefighter::TimeUnit;
 use crate::graph::Graph;
 
 /// Strategy to contain the fire in the firefighter problem
-#[derive(Debug, EnumString, EnumVariantNames)]
+#[derive(Debug, EnumString, EnumVariantNames, IntoStaticStr)]
 #[strum(serialize_all = "PascalCase")]
 pub enum OSMFStrategy {
     Greedy(GreedyStrategy),
+    GreedyEdges(GreedyEdgesStrategy),
     Score(ScoreStrategy),
     MultiMinDistanceSets(MultiMinDistSetsStrategy),
     SingleMinDistanceSet(SingleMinDistSetStrategy),
     Priority(PriorityStrategy),
+    Centrality(CentralityStrategy),
+    TargetCut(TargetCutStrategy),
+    MinCut(MinCutStrategy),
+    Dominator(DominatorStrategy),
+    Tree(TreeStrategy),
+    Rollout(RolloutStrategy),
+    Annealing(AnnealingStrategy),
+    Exact(ExactStrategy),
     Random(RandomStrategy),
+    /// Strategy registered outside of this crate
+    #[strum(disabled)]
+    Custom(CustomStrategy),
 }
 
 impl OSMFStrategy {
-    /// Returns a list of available fire containment strategies
+    /// Returns a list of available fire containment strategies, including all registered
+    /// custom strategies
     pub fn available_strategies() -> Vec<String> {
         Self::VARIANTS.iter()
+            .filter(|&&name| name != "Custom")
             .map(<&str>::to_string)
+            .chain(registry::registered_strategies())
             .collect::<Vec<_>>()
     }
 
+    /// Returns all available fire containment strategies together with their tunable parameters
+    pub fn available_strategies_with_parameters() -> Vec<OSMFStrategyInfo> {
+        Self::available_strategies().into_iter()
+            .map(|name| OSMFStrategyInfo {
+                parameters: Self::parameter_schema(&name).unwrap_or_default(),
+                name,
+            })
+            .collect()
+    }
+
+    /// Returns the tunable parameters of the strategy with given name
+    pub fn parameter_schema(strategy_name: &str) -> Option<Vec<OSMFParameterSpec>> {
+        match strategy_name {
+            "Greedy" => Some(GreedyStrategy::parameters()),
+            "Score" => Some(ScoreStrategy::parameters()),
+            "Priority" => Some(PriorityStrategy::parameters()),
+            "Centrality" => Some(CentralityStrategy::parameters()),
+            "MinCut" => Some(MinCutStrategy::parameters()),
+            "Rollout" => Some(RolloutStrategy::parameters()),
+            "Tree" => Some(TreeStrategy::parameters()),
+            "Annealing" => Some(AnnealingStrategy::parameters()),
+            _ if Self::is_built_in(strategy_name) => Some(Vec::new()),
+            _ => registry::registered_parameters(strategy_name)
+        }
+    }
+
+    /// Returns the name of this strategy
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Custom(ref strategy) => strategy.name(),
+            _ => self.into()
+        }
+    }
+
+    /// Returns true if `strategy_name` is reserved by a built-in strategy
+    pub(super) fn is_built_in(strategy_name: &str) -> bool {
+        Self::VARIANTS.contains(&strategy_name)
+    }
+
     /// Return a new strategy with given name that operates on given graph
     pub fn from_name_and_graph(strategy_name: &str, graph: Arc<Graph>) -> Option<Self> {
         match strategy_name {
             "Greedy" => Some(Self::Greedy(GreedyStrategy::new(graph))),
+            "GreedyEdges" => Some(Self::GreedyEdges(GreedyEdgesStrategy::new(graph))),
             "Score" => Some(Self::Score(ScoreStrategy::new(graph))),
             "MultiMinDistanceSets" => Some(Self::MultiMinDistanceSets(MultiMinDistSetsStrategy::new(graph))),
             "SingleMinDistanceSet" => Some(Self::SingleMinDistanceSet(SingleMinDistSetStrategy::new(graph))),
             "Priority" => Some(Self::Priority(PriorityStrategy::new(graph))),
+            "Centrality" => Some(Self::Centrality(CentralityStrategy::new(graph))),
+            "TargetCut" => Some(Self::TargetCut(TargetCutStrategy::new(graph))),
+            "MinCut" => Some(Self::MinCut(MinCutStrategy::new(graph))),
+            "Dominator" => Some(Self::Dominator(DominatorStrategy::new(graph))),
+            "Tree" => Some(Self::Tree(TreeStrategy::new(graph))),
+            "Rollout" => Some(Self::Rollout(RolloutStrategy::new(graph))),
+            "Annealing" => Some(Self::Annealing(AnnealingStrategy::new(graph))),
+            "Exact" => Some(Self::Exact(ExactStrategy::new(graph))),
             "Random" => Some(Self::Random(RandomStrategy::new(graph))),
-            _ => None
+            _ => registry::create_strategy(strategy_name, graph).map(Self::Custom)
         }
     }
 
-    /// Returns a mutable reference to the strategy trait object contained in this strategy instance
-    pub(super) fn mut_inner(&mut self) -> &mut dyn Strategy {
-        match self {
+    /// Execute the fire containment strategy contained in this strategy instance
+    pub(super) fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage,
+                          global_time: TimeUnit) {
+        let strategy = match self {
             Self::Greedy(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::GreedyEdges(ref mut strategy) => strategy.as_mut_strategy(),
             Self::Score(ref mut strategy) => strategy.as_mut_strategy(),
             Self::MultiMinDistanceSets(ref mut strategy) => strategy.as_mut_strategy(),
             Self::SingleMinDistanceSet(ref mut strategy) => strategy.as_mut_strategy(),
             Self::Priority(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Centrality(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::TargetCut(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::MinCut(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Dominator(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Tree(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Rollout(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Annealing(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Exact(ref mut strategy) => strategy.as_mut_strategy(),
             Self::Random(ref mut strategy) => strategy.as_mut_strategy(),
+            Self::Custom(ref mut strategy) => {
+                strategy.execute(settings, node_data, global_time);
+                return;
+            }
+        };
+        strategy.execute(settings, node_data, global_time);
+    }
+
+    /// Validate the parameter values `values` and apply them to this strategy
+    pub(super) fn configure(&mut self, values: &BTreeMap<String, OSMFParameterValue>) -> Result<(), OSMFSettingsError> {
+        let schema = Self::parameter_schema(self.name()).unwrap_or_default();
+        let params = OSMFParameters::resolve(self.name(), &schema, values)?;
+        match self {
+            Self::Greedy(ref mut strategy) => strategy.configure(&params),
+            Self::Score(ref mut strategy) => strategy.configure(&params),
+            Self::Priority(ref mut strategy) => strategy.configure(&params),
+            Self::Centrality(ref mut strategy) => strategy.configure(&params)?,
+            Self::MinCut(ref mut strategy) => strategy.configure(&params),
+            Self::Tree(ref mut strategy) => strategy.configure(&params),
+            Self::Rollout(ref mut strategy) => strategy.configure(&params),
+            Self::Annealing(ref mut strategy) => strategy.configure(&params),
+            Self::Exact(ref mut strategy) => strategy.configure(&params)?,
+            Self::Custom(ref mut strategy) => strategy.configure(&params),
+            _ => ()
+        };
+        Ok(())
+    }
+
+    /// Returns true if this strategy can be used in the defense mode `defense_mode`
+    pub(super) fn supports(&self, defense_mode: OSMFDefenseMode) -> bool {
+        match self {
+            Self::GreedyEdges(_) => defense_mode != OSMFDefenseMode::Nodes,
+            _ => defense_mode == OSMFDefenseMode::Nodes,
         }
     }
 
@@ -67,6 +182,19 @@ impl OSMFStrategy {
             Self::SingleMinDistanceSet(ref mut strategy) => {
                 strategy.compute_nodes_to_defend(roots, settings);
             }
+            Self::Dominator(ref mut strategy) => {
+                strategy.add_undefended_roots(roots);
+                strategy.compute_tree(roots, settings, node_data);
+            }
+            Self::Annealing(ref mut strategy) => {
+                strategy.compute_plan(roots, settings, node_data);
+            }
+            Self::Exact(ref mut strategy) => {
+                strategy.compute_plan(roots, settings, node_data);
+            }
+            Self::Custom(ref mut strategy) => {
+                strategy.initialize(settings, node_data);
+            }
             Self::Priority(ref mut strategy) => {
                 strategy.initialize_undefended_roots(roots);
                 strategy.compute_nodes_to_defend(roots, settings, node_data);
@@ -74,6 +202,33 @@ impl OSMFStrategy {
             _ => ()
         };
     }
+
+    /// Notify this strategy that the nodes in `ignited` caught fire after time `0`, so that
+    /// strategies with cached plans can recompute them
+    pub(super) fn notify_ignitions(&mut self, ignited: &[usize], settings: &OSMFSettings,
+                                   node_data: &NodeDataStorage, global_time: TimeUnit) {
+        match self {
+            Self::MultiMinDistanceSets(ref mut strategy) => {
+                strategy.add_undefended_roots(ignited);
+                let roots: Vec<_> = strategy.undefended_roots.keys().copied().collect();
+                strategy.compute_nodes_to_defend(&roots, settings, node_data);
+            }
+            Self::Priority(ref mut strategy) => {
+                strategy.add_undefended_roots(ignited);
+                let roots: Vec<_> = strategy.undefended_roots.keys().copied().collect();
+                strategy.compute_nodes_to_defend(&roots, settings, node_data);
+            }
+            Self::Dominator(ref mut strategy) => {
+                strategy.add_undefended_roots(ignited);
+                let roots: Vec<_> = strategy.undefended_roots.keys().copied().collect();
+                strategy.compute_tree(&roots, settings, node_data);
+            }
+            Self::Custom(ref mut strategy) => {
+                strategy.notify_ignitions(ignited, settings, node_data, global_time);
+            }
+            _ => ()
+        };
+    }
 }
 
 /// Strategy trait that each strategy needs to implement
@@ -91,16 +246,70 @@ pub(super) trait Strategy {
     }
 }
 
+/// Importance of the node with id `node_id` with respect to the objective in `settings`.
+/// When maximizing the number of saved nodes, the importance of a node is its _out degree_.
+/// When maximizing the saved weight, the importance of a node is its weight.
+fn node_importance(graph: &Graph, settings: &OSMFSettings, node_id: usize) -> f64 {
+    match settings.objective {
+        OSMFObjective::SavedNodes => graph.get_node_degree(node_id) as f64,
+        OSMFObjective::SavedWeight => graph.get_node_weight(node_id),
+    }
+}
+
+/// Choose `settings.firebreaks.budget` firebreaks that are not in `excluded` with the static
+/// firebreak strategy in `settings`. The fire roots are unknown at this point.
+pub(super) fn select_firebreaks<R: Rng>(graph: &Graph, settings: &OSMFSettings, excluded: &[usize],
+                                        rng: &mut R) -> Vec<usize> {
+    let candidates = graph.nodes().iter()
+        .map(|node| node.id)
+        .filter(|node_id| !excluded.contains(node_id));
+    let budget = settings.firebreaks.budget;
+
+    match settings.firebreaks.strategy {
+        OSMFFirebreakStrategy::MostImportant => {
+            let mut candidates: Vec<_> = candidates.collect();
+            candidates.sort_by(|&n1, &n2| {
+                let imp1 = node_importance(graph, settings, n1);
+                let imp2 = node_importance(graph, settings, n2);
+                imp2.total_cmp(&imp1)
+            });
+            candidates.truncate(budget);
+            candidates
+        }
+        OSMFFirebreakStrategy::Random => candidates.choose_multiple(rng, budget),
+    }
+}
+
 /// Greedy fire containment strategy
 #[derive(Debug, Default)]
 pub struct GreedyStrategy {
     graph: Arc<Graph>,
+    /// Break ties between equally distant targets by their importance
+    tie_break_by_importance: bool,
+}
+
+impl GreedyStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![OSMFParameterSpec {
+            name: "tie_break",
+            description: "Order of targets at the same distance from the fire: most important \
+            targets first or in the order in which they are found",
+            param_type: OSMFParameterType::Choice { choices: vec!["Importance", "None"] },
+            default: OSMFParameterValue::Choice("Importance".to_string()),
+        }]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.tie_break_by_importance = params.choice("tie_break") == "Importance";
+    }
 }
 
 impl Strategy for GreedyStrategy {
     fn new(graph: Arc<Graph>) -> Self {
         Self {
             graph,
+            tie_break_by_importance: true,
         }
     }
 
@@ -117,16 +326,20 @@ impl Strategy for GreedyStrategy {
             }
         }
 
-        // Sort the edges by their weight and by the _out degree_ of their targets
-        edges.sort_unstable_by(|&e1, &e2|
-            e1.dist.cmp(&e2.dist).then_with(|| {
-                let tgt1_deg = self.graph.get_node_degree(e1.tgt);
-                let tgt2_deg = self.graph.get_node_degree(e2.tgt);
-                tgt2_deg.cmp(&tgt1_deg)
-            }));
+        // Sort the edges by their weight and by the importance of their targets
+        if self.tie_break_by_importance {
+            edges.sort_unstable_by(|&e1, &e2|
+                e1.dist.cmp(&e2.dist).then_with(|| {
+                    let tgt1_imp = node_importance(&self.graph, settings, e1.tgt);
+                    let tgt2_imp = node_importance(&self.graph, settings, e2.tgt);
+                    tgt2_imp.total_cmp(&tgt1_imp)
+                }));
+        } else {
+            edges.sort_by_key(|e| e.dist);
+        }
 
         // Defend as many targets as firefighters are available
-        let num_to_defend = min(edges.len(), settings.num_ffs);
+        let num_to_defend = min(edges.len(), settings.firefighters_at(global_time));
         let to_defend: Vec<_> = edges[0..num_to_defend].iter()
             .map(|&e| e.tgt)
             .collect();
@@ -134,11 +347,82 @@ impl Strategy for GreedyStrategy {
     }
 }
 
+/// Greedy fire containment strategy that protects edges instead of defending nodes
+#[derive(Debug, Default)]
+pub struct GreedyEdgesStrategy {
+    graph: Arc<Graph>,
+}
+
+impl Strategy for GreedyEdgesStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let burning = node_data.get_burning();
+
+        // Get all edges along which the fire can still spread to nodes that are not burned yet
+        let mut edges = Vec::new();
+        for node_id in burning {
+            for edge in self.graph.get_outgoing_edges(node_id) {
+                if node_data.is_undefended(&edge.tgt) && !node_data.is_blocked(&edge.src, &edge.tgt) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        // Sort the edges by their weight and by the importance of their targets
+        edges.sort_unstable_by(|&e1, &e2|
+            e1.dist.cmp(&e2.dist).then_with(|| {
+                let tgt1_imp = node_importance(&self.graph, settings, e1.tgt);
+                let tgt2_imp = node_importance(&self.graph, settings, e2.tgt);
+                tgt2_imp.total_cmp(&tgt1_imp)
+            }));
+
+        // Protect as many edges as firefighters are available
+        let num_to_protect = min(edges.len(), settings.firefighters_at(global_time));
+        let to_protect: Vec<_> = edges[0..num_to_protect].iter()
+            .map(|&e| (e.src, e.tgt))
+            .collect();
+        node_data.mark_protected(&to_protect, global_time);
+    }
+}
+
 /// Score based fire containment strategy
 #[derive(Debug, Default)]
 pub struct ScoreStrategy {
     graph: Arc<Graph>,
+    distances: IncrementalDistances,
     node_degrees: Vec<usize>,
+    distance_weight: f64,
+    importance_weight: f64,
+}
+
+impl ScoreStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![
+            OSMFParameterSpec {
+                name: "distance_weight",
+                description: "Weight of the normalized closeness of a node to the fire in its score",
+                param_type: OSMFParameterType::Float { min: 0.0, max: 100.0 },
+                default: OSMFParameterValue::Float(2.0),
+            },
+            OSMFParameterSpec {
+                name: "importance_weight",
+                description: "Weight of the normalized importance of a node in its score",
+                param_type: OSMFParameterType::Float { min: 0.0, max: 100.0 },
+                default: OSMFParameterValue::Float(1.0),
+            },
+        ]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.distance_weight = params.float("distance_weight");
+        self.importance_weight = params.float("importance_weight");
+    }
 }
 
 impl Strategy for ScoreStrategy {
@@ -148,14 +432,21 @@ impl Strategy for ScoreStrategy {
             .map(|node| graph.get_node_degree(node.id))
             .collect();
         Self {
+            distances: IncrementalDistances::without_blocking(graph.clone()),
             graph,
             node_degrees,
+            distance_weight: 2.0,
+            importance_weight: 1.0,
         }
     }
 
     fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
-        // Run burning-to-all dijkstra to compute shortest distances for all nodes to the fire
-        let dists = self.graph.run_dijkstra(node_data.get_burning().as_slice());
+        // Update the shortest distances of all nodes to the fire
+        let sources: Vec<_> = node_data.get_burning().into_iter()
+            .map(|node_id| (node_id, 0))
+            .collect();
+        self.distances.update(&sources, node_data);
+        let dists = self.distances.dists();
 
         // Compute max distance for normalization
         let maybe_max_dist = self.graph.nodes().iter()
@@ -167,30 +458,39 @@ impl Strategy for ScoreStrategy {
             None => return // Nothing left to defend
         };
 
-        // Compute max degree for normalization
-        let max_deg = self.graph.nodes().iter()
+        // The importance of a node is its degree or its weight, depending on the objective
+        let importance = |node_id: usize| match settings.objective {
+            OSMFObjective::SavedNodes => self.node_degrees[node_id] as f64,
+            OSMFObjective::SavedWeight => self.graph.get_node_weight(node_id),
+        };
+
+        // Compute max importance for normalization.
+        // If all importances are 0, e.g. all weights are 0, all normalized importances are 0.
+        let max_imp = self.graph.nodes().iter()
             .filter(|&node| node_data.is_undefended(&node.id) && dists[node.id] < usize::MAX)
-            .map(|node| self.node_degrees[node.id])
-            .max()
-            .unwrap(); // Unwrap because iterator cannot be empty
+            .map(|node| importance(node.id))
+            .fold(0.0, f64::max)
+            .max(f64::MIN_POSITIVE);
 
-        // Compute normalized scores and sort them in descending order
+        // Compute normalized scores and sort them in descending order.
+        // If both weights are 0, all scores are 0.
+        let total_weight = (self.distance_weight + self.importance_weight).max(f64::MIN_POSITIVE);
         let mut scores: Vec<_> = self.graph.nodes().iter()
             .filter(|&node| node_data.is_undefended(&node.id) && dists[node.id] < usize::MAX)
             .map(|node| {
                 let norm_dist_score = 1.0 - dists[node.id] as f64 / max_dist as f64;
-                let norm_deg_score = self.node_degrees[node.id] as f64 / max_deg as f64;
-                let score = (2.0 * norm_dist_score + norm_deg_score) / 3.0;
+                let norm_deg_score = importance(node.id) / max_imp;
+                let score = (self.distance_weight * norm_dist_score
+                    + self.importance_weight * norm_deg_score) / total_weight;
                 (node.id, score)
             })
             .collect();
-        scores.sort_unstable_by(|(_, score1), &(_, score2)|
-            score2.partial_cmp(score1).unwrap());
+        scores.sort_unstable_by(|(_, score1), (_, score2)| score2.total_cmp(score1));
 
         log::debug!("Scores: {:?}", &scores);
 
         // Defend as many targets as firefighters are available
-        let num_to_defend = min(scores.len(), settings.num_ffs);
+        let num_to_defend = min(scores.len(), settings.firefighters_at(global_time));
         let to_defend: Vec<_> = scores[0..num_to_defend].iter()
             .map(|&(node_id, _)| node_id)
             .collect();
@@ -198,6 +498,118 @@ impl Strategy for ScoreStrategy {
     }
 }
 
+/// Centrality based fire containment strategy.
+/// Every round, the undefended nodes that the fire can reach are ranked by their centrality in
+/// the graph, e.g. their betweenness, divided by a power of their distance to the fire, and the
+/// highest ranked nodes are defended. Centralities are computed once per graph and shared by all
+/// strategy instances on that graph.
+#[derive(Debug, Default)]
+pub struct CentralityStrategy {
+    graph: Arc<Graph>,
+    distances: IncrementalDistances,
+    centrality: Centrality,
+    /// Centralities of all nodes, which are fetched from the graph on first use
+    centralities: Option<Arc<Vec<f64>>>,
+    distance_exponent: f64,
+}
+
+impl CentralityStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![
+            OSMFParameterSpec {
+                name: "centrality",
+                description: "Centrality measure that determines the importance of a node",
+                param_type: OSMFParameterType::Choice {
+                    choices: vec!["Betweenness", "SampledBetweenness", "Closeness", "PageRank"]
+                },
+                default: OSMFParameterValue::Choice("SampledBetweenness".to_string()),
+            },
+            OSMFParameterSpec {
+                name: "num_samples",
+                description: "Number of source nodes from which the sampled betweenness is \
+                estimated",
+                param_type: OSMFParameterType::Integer { min: 1, max: EXACT_CENTRALITY_NODE_LIMIT as i64 },
+                default: OSMFParameterValue::Integer(DEFAULT_BETWEENNESS_SAMPLES as i64),
+            },
+            OSMFParameterSpec {
+                name: "distance_exponent",
+                description: "Exponent of the distance of a node to the fire, by whose power the \
+                centrality of the node is divided in its score",
+                param_type: OSMFParameterType::Float { min: 0.0, max: 10.0 },
+                default: OSMFParameterValue::Float(2.0),
+            },
+        ]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) -> Result<(), OSMFSettingsError> {
+        let centrality = match params.choice("centrality") {
+            "Betweenness" => Centrality::Betweenness,
+            "Closeness" => Centrality::Closeness,
+            "PageRank" => Centrality::PageRank,
+            _ => Centrality::SampledBetweenness {
+                num_samples: params.integer("num_samples") as usize,
+            },
+        }.for_num_nodes(self.graph.num_nodes);
+        if centrality.is_exact() && self.graph.num_nodes > EXACT_CENTRALITY_NODE_LIMIT {
+            return Err(OSMFSettingsError::InvalidStrategyParameter {
+                strategy_name: "Centrality".to_string(),
+                reason: format!("{:?} centrality is limited to graphs with at most {} nodes: {}",
+                                centrality, EXACT_CENTRALITY_NODE_LIMIT, self.graph.num_nodes),
+            });
+        }
+        self.centrality = centrality;
+        self.centralities = None;
+        self.distance_exponent = params.float("distance_exponent");
+        Ok(())
+    }
+}
+
+impl Strategy for CentralityStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            distances: IncrementalDistances::new(graph.clone()),
+            graph,
+            centrality: Centrality::default(),
+            centralities: None,
+            distance_exponent: 2.0,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let graph = self.graph.clone();
+        let centrality = self.centrality;
+        let centralities = self.centralities
+            .get_or_insert_with(|| graph.centrality(centrality))
+            .clone();
+
+        // Update the shortest distances of all nodes to the fire
+        let sources: Vec<_> = node_data.get_burning().into_iter()
+            .map(|node_id| (node_id, 0))
+            .collect();
+        self.distances.update(&sources, node_data);
+        let dists = self.distances.dists();
+
+        // Rank the undefended nodes that the fire can still reach by their centrality per
+        // distance to the fire, preferring closer nodes among equally ranked nodes
+        let mut scores: Vec<_> = (0..graph.num_nodes)
+            .filter(|&node_id| node_data.is_undefended(&node_id) && dists[node_id] < usize::MAX)
+            .map(|node_id| {
+                let dist = dists[node_id].max(1) as f64;
+                (node_id, centralities[node_id] / dist.powf(self.distance_exponent))
+            })
+            .collect();
+        scores.sort_by(|&(node1, score1), &(node2, score2)| score2.total_cmp(&score1)
+            .then_with(|| dists[node1].cmp(&dists[node2])));
+
+        let to_defend: Vec<_> = scores.into_iter()
+            .map(|(node_id, _)| node_id)
+            .take(settings.firefighters_at(global_time))
+            .collect();
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
 /// Type alias for clarification
 type Visited = HashSet<usize>;
 /// Type alias for clarification
@@ -249,11 +661,16 @@ fn compute_undefended_roots(undefended_roots: &mut HashMap<usize, (Visited, Risk
 }
 
 /// For every node, compute the minimum shortest distance between the node and any fire root.
+/// Roots that caught fire after time `0` are offset by the time at which they caught fire.
 /// Then, group the nodes by minimum shortest distance.
-fn group_nodes_by_distance(undefended_roots: &Vec<usize>, graph: &Arc<Graph>,
+fn group_nodes_by_distance(undefended_roots: &[usize], distances: &mut IncrementalDistances,
                            node_data: &NodeDataStorage) -> BTreeMap<usize, Vec<usize>> {
-    let dists = graph.run_dijkstra(undefended_roots.as_slice());
-    let mut sho_dists = HashMap::with_capacity(graph.num_nodes);
+    let sources: Vec<_> = undefended_roots.iter()
+        .map(|&root| (root, node_data.get_burning_time(&root).unwrap_or(0) as usize))
+        .collect();
+    distances.update(&sources, node_data);
+    let dists = distances.dists();
+    let mut sho_dists = HashMap::with_capacity(dists.len());
     for (node, &dist) in dists.iter().enumerate() {
         if node_data.is_undefended(&node) && dist < usize::MAX {
             sho_dists.insert(node, dist);
@@ -277,6 +694,7 @@ fn group_nodes_by_distance(undefended_roots: &Vec<usize>, graph: &Arc<Graph>,
 #[derive(Debug, Default)]
 pub struct MultiMinDistSetsStrategy {
     graph: Arc<Graph>,
+    distances: IncrementalDistances,
     nodes_to_defend: VecDeque<usize>,
     possible_defended: usize,
     undefended_roots: HashMap<usize, (Visited, RiskyNodes)>,
@@ -290,6 +708,14 @@ impl MultiMinDistSetsStrategy {
             self.undefended_roots.insert(root, (HashSet::new(), HashSet::from([root])));
         }
     }
+
+    /// Add secondary ignitions to the undefended roots datastructure
+    fn add_undefended_roots(&mut self, roots: &[usize]) {
+        self.undefended_roots.reserve(roots.len());
+        for &root in roots {
+            self.undefended_roots.insert(root, (HashSet::new(), HashSet::from([root])));
+        }
+    }
     
     /// (Re-)compute undefended roots by tracking paths through burning vertices from
     /// all roots to any undefended node.
@@ -300,13 +726,11 @@ impl MultiMinDistSetsStrategy {
     }
     
     /// Compute nodes to defend and order in which nodes should be defended
-    pub(super) fn compute_nodes_to_defend(&mut self, undefended_roots: &Vec<usize>, settings: &OSMFSettings,
+    pub(super) fn compute_nodes_to_defend(&mut self, undefended_roots: &[usize], settings: &OSMFSettings,
                                    node_data: &NodeDataStorage) {
         let mut nodes_by_sho_dist = group_nodes_by_distance(undefended_roots,
-                                                            &self.graph, node_data);
+                                                            &mut self.distances, node_data);
 
-        let strategy_every = settings.strategy_every as usize;
-        let num_ffs = settings.num_ffs;
         let mut total_defended = self.possible_defended;
 
         // Node groups that can be defended completely
@@ -314,7 +738,7 @@ impl MultiMinDistSetsStrategy {
         let mut defend_completely = Vec::with_capacity(nodes_by_sho_dist.len());
         nodes_by_sho_dist = nodes_by_sho_dist.into_iter()
             .filter(|(dist, nodes)| {
-                let can_defend_total = *dist / strategy_every * num_ffs;
+                let can_defend_total = settings.firefighters_by(*dist as TimeUnit);
                 if can_defend_total > total_defended {
                     let must_defend = nodes.len();
                     let can_defend = can_defend_total - total_defended;
@@ -336,17 +760,17 @@ impl MultiMinDistSetsStrategy {
             orig_len - defend_completely.len());
         for (&dist, nodes) in nodes_by_sho_dist.iter() {
             let must_defend = nodes.len();
-            let could_defend_total = dist / strategy_every * num_ffs;
+            let could_defend_total = settings.firefighters_by(dist as TimeUnit);
             if could_defend_total > total_defended {
                 let can_defend = could_defend_total - total_defended;
                 if can_defend < must_defend  {
                     total_defended += can_defend;
-                    // Sort by out degree
+                    // Sort by importance
                     let mut nodes = nodes.clone();
                     nodes.sort_unstable_by(|&n1, &n2| {
-                        let deg1 = self.graph.get_node_degree(n1);
-                        let deg2 = self.graph.get_node_degree(n2);
-                        deg2.cmp(&deg1)
+                        let imp1 = node_importance(&self.graph, settings, n1);
+                        let imp2 = node_importance(&self.graph, settings, n2);
+                        imp2.total_cmp(&imp1)
                     });
                     // Take first 'can_defend' number of nodes
                     defend_partially.push(nodes[0..can_defend].to_vec());
@@ -376,6 +800,7 @@ impl MultiMinDistSetsStrategy {
 impl Strategy for MultiMinDistSetsStrategy {
     fn new(graph: Arc<Graph>) -> Self {
         Self {
+            distances: IncrementalDistances::without_blocking(graph.clone()),
             graph,
             nodes_to_defend: VecDeque::new(),
             possible_defended: 0,
@@ -384,18 +809,19 @@ impl Strategy for MultiMinDistSetsStrategy {
     }
 
     fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
-        let num_to_defend = min(settings.num_ffs, self.nodes_to_defend.len());
+        let num_to_defend = min(settings.firefighters_at(global_time), self.nodes_to_defend.len());
         let len = self.nodes_to_defend.len();
         let to_defend = &self.nodes_to_defend.as_slices().0[(len-num_to_defend)..len];
 
         for node in to_defend {
-            assert!(node_data.is_undefended(node));
+            // In the mobility model, nodes may catch fire before a firefighter reaches them
+            assert!(node_data.is_mobile() || node_data.is_undefended(node));
         }
 
         node_data.mark_defended(to_defend, global_time);
 
         self.nodes_to_defend.truncate(len-num_to_defend);
-        self.possible_defended += settings.num_ffs;
+        self.possible_defended = settings.firefighters_by(global_time);
 
         // One or more fire roots have been defended and hence shouldn't be considered
         // in the min_distance_groups anymore
@@ -462,12 +888,9 @@ impl SingleMinDistSetStrategy {
 
         log::debug!("Distance nodes map:\n{:?}", &distance_nodes_map);
 
-        let strategy_every = settings.strategy_every as usize;
-        let num_ffs = settings.num_ffs as usize;
-
         let maybe_nodes = distance_nodes_map.into_iter()
             .find_map(|(dist, nodes)|
-                if nodes.len() <= dist / strategy_every * num_ffs {
+                if nodes.len() <= settings.firefighters_by(dist as TimeUnit) {
                     log::debug!("Selected {} nodes to defend: {:?} with distance {}",
                             nodes.len(), &nodes, dist);
                     Some(nodes)
@@ -491,7 +914,8 @@ impl Strategy for SingleMinDistSetStrategy {
     }
 
     fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
-        let num_to_defend = min(settings.num_ffs, self.nodes_to_defend.len() - self.current_defended);
+        let num_to_defend = min(settings.firefighters_at(global_time),
+                                self.nodes_to_defend.len() - self.current_defended);
         let to_defend = &self.nodes_to_defend[self.current_defended..self.current_defended + num_to_defend];
         node_data.mark_defended(to_defend, global_time);
 
@@ -503,12 +927,29 @@ impl Strategy for SingleMinDistSetStrategy {
 #[derive(Debug, Default)]
 pub struct PriorityStrategy {
     graph: Arc<Graph>,
+    distances: IncrementalDistances,
     nodes_to_defend: VecDeque<usize>,
     possible_defended: usize,
     undefended_roots: HashMap<usize, (Visited, RiskyNodes)>,
+    /// Quantile of the priorities that separates high from low priority nodes
+    quantile: f64,
 }
 
 impl PriorityStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![OSMFParameterSpec {
+            name: "quantile",
+            description: "Quantile of the node priorities above which nodes are defended first",
+            param_type: OSMFParameterType::Float { min: 0.0, max: 1.0 },
+            default: OSMFParameterValue::Float(0.25),
+        }]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.quantile = params.float("quantile");
+    }
+
     /// Initialize the undefended roots datastructure
     pub(super) fn initialize_undefended_roots(&mut self, roots: &Vec<usize>) {
         self.undefended_roots.reserve(roots.len());
@@ -517,6 +958,14 @@ impl PriorityStrategy {
         }
     }
 
+    /// Add secondary ignitions to the undefended roots datastructure
+    fn add_undefended_roots(&mut self, roots: &[usize]) {
+        self.undefended_roots.reserve(roots.len());
+        for &root in roots {
+            self.undefended_roots.insert(root, (HashSet::new(), HashSet::from([root])));
+        }
+    }
+
     /// (Re-)compute undefended roots by tracking paths through burning vertices from
     /// all roots to any undefended node.
     /// Returns the remaining undefended roots, if the number of undefended roots
@@ -526,12 +975,12 @@ impl PriorityStrategy {
     }
     
     /// Compute nodes to defend and order in which nodes should be defended
-    pub(super) fn compute_nodes_to_defend(&mut self, undefended_roots: &Vec<usize>, settings: &OSMFSettings,
+    pub(super) fn compute_nodes_to_defend(&mut self, undefended_roots: &[usize], settings: &OSMFSettings,
                                    node_data: &NodeDataStorage) {
         let mut priority_map = HashMap::with_capacity(self.graph.num_nodes);
         for node in self.graph.nodes() {
             if node_data.is_undefended(&node.id) && self.graph.get_node_degree(node.id) > 0 {
-                let prio = self.graph.get_node_degree(node.id);
+                let prio = node_importance(&self.graph, settings, node.id);
                 // for i in graph.offsets[node.id]..graph.offsets[node.id+1] {
                 //     let edge = &graph.edges[i];
                 //     prio += 1.0 / edge.dist as f64;
@@ -543,40 +992,48 @@ impl PriorityStrategy {
         log::debug!("Computed priority map:\n{:?}", &priority_map);
 
         let mut sorted_priorities: Vec<_> = priority_map.values().map(|prio|*prio).collect();
-        sorted_priorities.sort_unstable_by(usize::cmp);
+        sorted_priorities.sort_unstable_by(|prio1, prio2| prio1.total_cmp(prio2));
         // let mean = priority_map.values().sum::<f64>() as f64 / priority_map.len() as f64;
         // log::debug!("Computed mean: {}", mean);
-        let q25 = if sorted_priorities.len() % 4 != 0 {
-            sorted_priorities[(sorted_priorities.len() / 4) + 1]
+        let last = sorted_priorities.len().saturating_sub(1);
+        let position = sorted_priorities.len() as f64 * self.quantile;
+        let index = position as usize;
+        let quantile = if sorted_priorities.is_empty() {
+            0.0
+        } else if position.fract() != 0.0 {
+            sorted_priorities[min(index + 1, last)]
         } else {
-            (sorted_priorities[sorted_priorities.len() / 4]
-                + sorted_priorities[(sorted_priorities.len() / 4) + 1]) / 2
+            let mean = (sorted_priorities[min(index, last)]
+                + sorted_priorities[min(index + 1, last)]) / 2.0;
+            match settings.objective {
+                // Degrees are integral
+                OSMFObjective::SavedNodes => mean.floor(),
+                OSMFObjective::SavedWeight => mean,
+            }
         };
-        log::debug!("Computed 25 percent quantile: {}", q25);
+        log::debug!("Computed {} quantile: {}", self.quantile, quantile);
 
         let mut nodes_by_sho_dist = group_nodes_by_distance(undefended_roots,
-                                                        &self.graph, node_data);
+                                                        &mut self.distances, node_data);
 
         // Sort Node groups by priority
         for (_, nodes) in nodes_by_sho_dist.iter_mut() {
             nodes.sort_unstable_by(|n1, n2| {
-                let prio1 = priority_map.get(n1).unwrap_or(&0);
-                let prio2 = priority_map.get(n2).unwrap_or(&0);
-                prio2.cmp(&prio1)
+                let prio1 = priority_map.get(n1).unwrap_or(&0.0);
+                let prio2 = priority_map.get(n2).unwrap_or(&0.0);
+                prio2.total_cmp(prio1)
             });
         }
 
         log::debug!("Distance sets after sorting by priority:\n{:?}", &nodes_by_sho_dist);
 
-        let strategy_every = settings.strategy_every as usize;
-        let num_ffs = settings.num_ffs;
         let mut total_defended = self.possible_defended;
 
         // Filter nodes with higher priority based on mean
         let mut high_prio_map: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
         for (&dist, nodes) in nodes_by_sho_dist.iter() {
             let high_prio_nodes: Vec<_> = nodes.iter()
-                .filter(|&node| *priority_map.get(node).unwrap_or(&0) >= q25)
+                .filter(|&node| *priority_map.get(node).unwrap_or(&0.0) >= quantile)
                 .map(|node| *node)
                 .collect();
             high_prio_map.insert(dist, high_prio_nodes);
@@ -586,7 +1043,7 @@ impl PriorityStrategy {
         let mut low_prio_map: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
         for (&dist, nodes) in nodes_by_sho_dist.iter() {
             let low_prio_nodes: Vec<_> = nodes.iter()
-                .filter(|&node| *priority_map.get(node).unwrap_or(&0) < q25)
+                .filter(|&node| *priority_map.get(node).unwrap_or(&0.0) < quantile)
                 .map(|node| *node)
                 .collect();
             low_prio_map.insert(dist, low_prio_nodes);
@@ -595,7 +1052,7 @@ impl PriorityStrategy {
         // Nodes with a higher priority than the mean should be defended
         let mut high_prio_defend = Vec::new();
         for (&dist, nodes) in high_prio_map.iter() {
-            let can_defend_total = dist / strategy_every * num_ffs;
+            let can_defend_total = settings.firefighters_by(dist as TimeUnit);
             if can_defend_total > total_defended {
                 let can_defend = can_defend_total - total_defended;
                 let num_of_nodes = min(can_defend, nodes.len());
@@ -610,7 +1067,7 @@ impl PriorityStrategy {
         // Nodes with a lower priority than the mean should be defended
         let mut low_prio_defend = Vec::with_capacity(self.graph.num_nodes - high_prio_defend.len());
         for (&dist, nodes) in low_prio_map.iter() {
-            let can_defend_total = dist / strategy_every * num_ffs;
+            let can_defend_total = settings.firefighters_by(dist as TimeUnit);
             if can_defend_total > total_defended {
                 let can_defend = can_defend_total - total_defended;
                 let num_of_nodes = min(can_defend, nodes.len());
@@ -640,26 +1097,29 @@ impl PriorityStrategy {
 impl Strategy for PriorityStrategy {
     fn new(graph: Arc<Graph>) -> Self {
         Self {
+            distances: IncrementalDistances::without_blocking(graph.clone()),
             graph,
             nodes_to_defend: VecDeque::new(),
             possible_defended: 0,
             undefended_roots: HashMap::new(),
+            quantile: 0.25,
         }
     }
 
     fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
-        let num_to_defend = min(settings.num_ffs, self.nodes_to_defend.len());
+        let num_to_defend = min(settings.firefighters_at(global_time), self.nodes_to_defend.len());
         let len = self.nodes_to_defend.len();
         let to_defend = &self.nodes_to_defend.as_slices().0[(len-num_to_defend)..len];
 
         for node in to_defend {
-            assert!(node_data.is_undefended(node));
+            // In the mobility model, nodes may catch fire before a firefighter reaches them
+            assert!(node_data.is_mobile() || node_data.is_undefended(node));
         }
 
         node_data.mark_defended(to_defend, global_time);
 
         self.nodes_to_defend.truncate(len-num_to_defend);
-        self.possible_defended += settings.num_ffs;
+        self.possible_defended = settings.firefighters_by(global_time);
 
         // One or more fire roots have been defended and hence shouldn't be considered
         // in the min_distance_groups anymore
@@ -669,6 +1129,1163 @@ impl Strategy for PriorityStrategy {
     }
 }
 
+/// Fire containment strategy that separates the fire from the target nodes by defending a
+/// minimum vertex cut between the burning nodes and the targets.
+/// The nodes of the cut that the fire reaches first are defended first. Once the targets are
+/// separated from the fire, the remaining firefighters defend the nodes closest to the fire.
+#[derive(Debug, Default)]
+pub struct TargetCutStrategy {
+    graph: Arc<Graph>,
+    distances: IncrementalDistances,
+}
+
+impl Strategy for TargetCutStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            distances: IncrementalDistances::without_blocking(graph.clone()),
+            graph,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        // Burned out nodes do not spread the fire anymore
+        let burning: Vec<_> = node_data.get_burning().into_iter()
+            .filter(|node_id| !node_data.is_burned_out_by(node_id, &global_time))
+            .collect();
+        let targets: Vec<_> = settings.target_nodes(&self.graph).into_iter()
+            .filter(|node_id| node_data.is_undefended(node_id))
+            .collect();
+
+        let cut = self.graph.min_vertex_cut(&burning, &targets, |node_id|
+            node_data.is_defended(&node_id) || node_data.is_burned_out_by(&node_id, &global_time));
+        let sources: Vec<_> = burning.iter()
+            .map(|&node_id| (node_id, 0))
+            .collect();
+        self.distances.update(&sources, node_data);
+        let dists = self.distances.dists();
+
+        // Defend the cut nodes that the fire reaches first, then the nodes closest to the fire
+        let mut cut: Vec<_> = cut.into_iter()
+            .filter(|&node_id| dists[node_id] < usize::MAX)
+            .collect();
+        cut.sort_unstable_by_key(|&node_id| dists[node_id]);
+        let others = closest_undefended(&self.graph, settings, node_data, dists, &cut);
+
+        let to_defend: Vec<_> = cut.into_iter()
+            .chain(others)
+            .take(settings.firefighters_at(global_time))
+            .collect();
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+/// Returns the undefended nodes that are reachable according to the distances `dists` and that
+/// are not in `excluded`, ordered by their distance and then by their importance
+fn closest_undefended(graph: &Graph, settings: &OSMFSettings, node_data: &NodeDataStorage,
+                      dists: &[usize], excluded: &[usize]) -> Vec<usize> {
+    let mut nodes: Vec<_> = graph.nodes().iter()
+        .map(|node| node.id)
+        .filter(|node_id| node_data.is_undefended(node_id) && dists[*node_id] < usize::MAX)
+        .filter(|node_id| !excluded.contains(node_id))
+        .collect();
+    nodes.sort_unstable_by(|&n1, &n2|
+        dists[n1].cmp(&dists[n2]).then_with(|| {
+            let imp1 = node_importance(graph, settings, n1);
+            let imp2 = node_importance(graph, settings, n2);
+            imp2.total_cmp(&imp1)
+        }));
+    nodes
+}
+
+/// Fire containment strategy that defends bottlenecks between the fire and the largest region
+/// of undefended nodes that the fire can still reach.
+/// Every round, the region is split at several times: a minimum vertex cut separates the
+/// burning nodes from the nodes of the region that the fire reaches after that time. Among all
+/// cuts that can be defended before the fire reaches them, the one that saves the most is
+/// chosen, and its nodes that the fire reaches first are defended first. Remaining
+/// firefighters defend the nodes closest to the fire.
+#[derive(Debug, Default)]
+pub struct MinCutStrategy {
+    graph: Arc<Graph>,
+    /// Number of times at which the region is split
+    num_splits: usize,
+}
+
+impl MinCutStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![OSMFParameterSpec {
+            name: "num_splits",
+            description: "Number of times at which the region is split to compute a cut in each \
+            round. More splits find better cuts, but take longer.",
+            param_type: OSMFParameterType::Integer { min: 1, max: 64 },
+            default: OSMFParameterValue::Integer(8),
+        }]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.num_splits = params.integer("num_splits") as usize;
+    }
+
+    /// Returns the connected region of undefended nodes with the highest total cost among all
+    /// regions that the fire reaches according to `arrivals`
+    fn largest_region(&self, arrivals: &[usize], costs: &[f64], node_data: &NodeDataStorage) -> Vec<usize> {
+        let is_candidate = |node_id: usize| arrivals[node_id] < usize::MAX
+            && node_data.is_undefended(&node_id);
+        let mut visited = vec![false; self.graph.num_nodes];
+        let mut largest = (0.0, Vec::new());
+        for start in 0..self.graph.num_nodes {
+            if visited[start] || !is_candidate(start) {
+                continue;
+            }
+            visited[start] = true;
+            let mut region = vec![start];
+            let mut i = 0;
+            while i < region.len() {
+                for edge in self.graph.get_outgoing_edges(region[i]) {
+                    if !visited[edge.tgt] && is_candidate(edge.tgt) {
+                        visited[edge.tgt] = true;
+                        region.push(edge.tgt);
+                    }
+                }
+                i += 1;
+            }
+            let cost: f64 = region.iter().map(|&node_id| costs[node_id]).sum();
+            if cost > largest.0 {
+                largest = (cost, region);
+            }
+        }
+        largest.1
+    }
+
+    /// Returns the total cost of the nodes in the region `in_region` that the fire can still
+    /// reach from the nodes in `burning` if the nodes in `cut` are defended
+    fn reachable_cost(&self, burning: &[usize], cut: &[usize], in_region: &[bool], costs: &[f64]) -> f64 {
+        let mut visited = vec![false; self.graph.num_nodes];
+        for &node_id in burning.iter().chain(cut) {
+            visited[node_id] = true;
+        }
+        let mut queue: VecDeque<_> = burning.iter().copied().collect();
+        let mut cost = 0.0;
+        while let Some(node_id) = queue.pop_front() {
+            for edge in self.graph.get_outgoing_edges(node_id) {
+                if in_region[edge.tgt] && !visited[edge.tgt] {
+                    visited[edge.tgt] = true;
+                    cost += costs[edge.tgt];
+                    queue.push_back(edge.tgt);
+                }
+            }
+        }
+        cost
+    }
+}
+
+impl Strategy for MinCutStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+            num_splits: 8,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let budget = settings.firefighters_at(global_time);
+        if budget == 0 {
+            return;
+        }
+
+        // Burned out nodes do not spread the fire anymore
+        let is_blocked = |node_id: usize| node_data.is_defended(&node_id)
+            || node_data.is_burned_out_by(&node_id, &global_time);
+        let burning: Vec<_> = node_data.get_burning().into_iter()
+            .filter(|&node_id| !is_blocked(node_id))
+            .collect();
+        let sources: Vec<_> = burning.iter()
+            .map(|&node_id| (node_id, node_data.get_burning_time(&node_id).unwrap_or(0) as usize))
+            .collect();
+        let (arrivals, _) = self.graph.run_dijkstra_with_offsets_and_filter(&sources, |edge|
+            !is_blocked(edge.tgt));
+        let costs: Vec<_> = (0..self.graph.num_nodes)
+            .map(|node_id| match settings.objective {
+                OSMFObjective::SavedNodes => 1.0,
+                OSMFObjective::SavedWeight => self.graph.get_node_weight(node_id),
+            })
+            .collect();
+
+        let region = self.largest_region(&arrivals, &costs, node_data);
+        let mut in_region = vec![false; self.graph.num_nodes];
+        for &node_id in &region {
+            in_region[node_id] = true;
+        }
+        let region_cost: f64 = region.iter().map(|&node_id| costs[node_id]).sum();
+
+        // Number of firefighters that are deployed from now until the fire reaches a node at
+        // time `arrival`
+        let available = |arrival: usize| {
+            let arrival = (arrival as TimeUnit).max(global_time);
+            settings.firefighters_by(arrival) - settings.firefighters_by(global_time) + budget
+        };
+
+        // Split the region at evenly spaced arrival times. The region cannot be split at the
+        // latest arrival time.
+        let mut times: Vec<_> = region.iter().map(|&node_id| arrivals[node_id]).collect();
+        times.sort_unstable();
+        times.dedup();
+        let num_times = times.len().saturating_sub(1);
+        let mut splits: Vec<_> = (0..self.num_splits.min(num_times))
+            .map(|i| times[i * num_times / self.num_splits.min(num_times)])
+            .collect();
+        splits.dedup();
+
+        // Prefer cuts that can be defended in time, then cuts that save more, then smaller cuts
+        let mut best: Option<(bool, f64, Vec<usize>)> = None;
+        for split in splits {
+            let sinks: Vec<_> = region.iter()
+                .filter(|&&node_id| arrivals[node_id] > split)
+                .copied()
+                .collect();
+            let mut cut = self.graph.min_vertex_cut(&burning, &sinks, is_blocked);
+            cut.sort_unstable_by_key(|&node_id| arrivals[node_id]);
+            let feasible = cut.iter().enumerate()
+                .all(|(i, &node_id)| i < available(arrivals[node_id]));
+            let saved = region_cost - self.reachable_cost(&burning, &cut, &in_region, &costs);
+
+            log::debug!("Cut at time {}: feasible={}, saved={}, cut={:?}", split, feasible, saved, &cut);
+
+            let is_better = best.as_ref().is_none_or(|(best_feasible, best_saved, best_cut)|
+                (feasible, saved, std::cmp::Reverse(cut.len()))
+                    > (*best_feasible, *best_saved, std::cmp::Reverse(best_cut.len())));
+            if is_better {
+                best = Some((feasible, saved, cut));
+            }
+        }
+        let cut = best.map(|(_, _, cut)| cut).unwrap_or_default();
+
+        // Defend the cut nodes that the fire reaches first, then the nodes closest to the fire
+        let others = closest_undefended(&self.graph, settings, node_data, &arrivals, &cut);
+        let to_defend: Vec<_> = cut.into_iter()
+            .chain(others)
+            .take(budget)
+            .collect();
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+/// Fire containment strategy that defends nodes which dominate large parts of the graph.
+/// A node dominates another node if the fire can only reach the other node through it, so
+/// defending a node saves all nodes it dominates. The dominator tree is built from the
+/// undefended fire roots in the graph without defended nodes and rebuilt when roots become
+/// enclosed or new nodes ignite. Every round, the nodes with the highest number or weight of
+/// dominated nodes per distance to the fire are defended, skipping nodes that are dominated by
+/// defended nodes.
+#[derive(Debug, Default)]
+pub struct DominatorStrategy {
+    graph: Arc<Graph>,
+    distances: IncrementalDistances,
+    undefended_roots: HashMap<usize, (Visited, RiskyNodes)>,
+    /// Immediate dominator of each node
+    idoms: Vec<usize>,
+    /// Nodes in the dominator tree, each after its immediate dominator
+    order: Vec<usize>,
+    /// Number or weight of the nodes dominated by each node, including the node itself
+    dominated_costs: Vec<f64>,
+}
+
+impl DominatorStrategy {
+    /// Add fire roots or secondary ignitions to the undefended roots datastructure
+    fn add_undefended_roots(&mut self, roots: &[usize]) {
+        self.undefended_roots.reserve(roots.len());
+        for &root in roots {
+            self.undefended_roots.insert(root, (HashSet::new(), HashSet::from([root])));
+        }
+    }
+
+    /// Build the dominator tree from the fire roots in `roots`
+    pub(super) fn compute_tree(&mut self, roots: &[usize], settings: &OSMFSettings,
+                               node_data: &NodeDataStorage) {
+        let (idoms, order) = self.graph.dominator_tree(roots, |node_id|
+            node_data.is_defended(&node_id));
+
+        let mut dominated_costs = vec![0.0; self.graph.num_nodes];
+        for &node_id in order.iter().rev() {
+            dominated_costs[node_id] += match settings.objective {
+                OSMFObjective::SavedNodes => 1.0,
+                OSMFObjective::SavedWeight => self.graph.get_node_weight(node_id),
+            };
+            if idoms[node_id] < usize::MAX {
+                dominated_costs[idoms[node_id]] += dominated_costs[node_id];
+            }
+        }
+
+        log::debug!("Computed dominator tree of {} nodes from roots {:?}", order.len(), roots);
+
+        self.idoms = idoms;
+        self.order = order;
+        self.dominated_costs = dominated_costs;
+    }
+
+    /// Returns true if `node_id` or one of its dominators is in `nodes`
+    fn is_dominated_by(&self, node_id: usize, nodes: &[usize]) -> bool {
+        let mut dominator = node_id;
+        while dominator < usize::MAX {
+            if nodes.contains(&dominator) {
+                return true;
+            }
+            dominator = self.idoms[dominator];
+        }
+        false
+    }
+}
+
+impl Strategy for DominatorStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            distances: IncrementalDistances::new(graph.clone()),
+            graph,
+            undefended_roots: HashMap::new(),
+            idoms: Vec::new(),
+            order: Vec::new(),
+            dominated_costs: Vec::new(),
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        // Enclosed roots do not spread the fire to undefended nodes anymore
+        if let Some(roots) = compute_undefended_roots(&mut self.undefended_roots, &self.graph,
+                                                      node_data) {
+            self.compute_tree(&roots, settings, node_data);
+        }
+
+        // Nodes dominated by defended nodes are safe already
+        let mut is_safe = vec![false; self.graph.num_nodes];
+        for &node_id in &self.order {
+            let idom = self.idoms[node_id];
+            is_safe[node_id] = idom < usize::MAX
+                && (is_safe[idom] || node_data.is_defended(&idom));
+        }
+
+        let sources: Vec<_> = node_data.get_burning().into_iter()
+            .map(|node_id| (node_id, 0))
+            .collect();
+        self.distances.update(&sources, node_data);
+        let dists = self.distances.dists();
+        let mut ranked: Vec<_> = self.order.iter()
+            .filter(|&&node_id| node_data.is_undefended(&node_id) && !is_safe[node_id])
+            .filter(|&&node_id| dists[node_id] < usize::MAX)
+            .map(|&node_id| (node_id, self.dominated_costs[node_id] / dists[node_id].max(1) as f64))
+            .collect();
+        ranked.sort_by(|(_, rank1), (_, rank2)| rank2.total_cmp(rank1));
+
+        // Defending a node that is dominated by another defended node is pointless
+        let budget = settings.firefighters_at(global_time);
+        let mut to_defend = Vec::with_capacity(budget);
+        for (node_id, _) in ranked {
+            if to_defend.len() == budget {
+                break;
+            }
+            if !self.is_dominated_by(node_id, &to_defend) {
+                to_defend.push(node_id);
+            }
+        }
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+/// Fire containment strategy for tree-shaped spreads of the fire.
+/// Every round, the fire is assumed to spread along the shortest path tree from the burning
+/// nodes, in which defending a node saves its whole subtree. The nodes to defend are then
+/// chosen either by the greedy algorithm that defends the heaviest subtrees that the fire
+/// reaches first, or by an exact dynamic program if the tree is small enough.
+#[derive(Debug, Default)]
+pub struct TreeStrategy {
+    graph: Arc<Graph>,
+    use_exact: bool,
+    /// Maximum number of tree nodes for which the exact dynamic program is used
+    max_exact_nodes: usize,
+}
+
+impl TreeStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![
+            OSMFParameterSpec {
+                name: "algorithm",
+                description: "Algorithm that chooses the subtrees to save: the greedy \
+                2-approximation or the exact dynamic program",
+                param_type: OSMFParameterType::Choice { choices: vec!["Greedy", "Exact"] },
+                default: OSMFParameterValue::Choice("Greedy".to_string()),
+            },
+            OSMFParameterSpec {
+                name: "max_exact_nodes",
+                description: "Maximum size of the tree that is solved exactly, larger trees are \
+                solved greedily",
+                param_type: OSMFParameterType::Integer { min: 1, max: 1000 },
+                default: OSMFParameterValue::Integer(64),
+            },
+        ]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.use_exact = params.choice("algorithm") == "Exact";
+        self.max_exact_nodes = params.integer("max_exact_nodes") as usize;
+    }
+}
+
+impl Strategy for TreeStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+            use_exact: false,
+            max_exact_nodes: 64,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let tree = FireTree::shortest_path_tree(&self.graph, settings, node_data, global_time);
+        let max_arrival = match tree.max_arrival() {
+            Some(max_arrival) => max_arrival,
+            None => return,
+        };
+
+        // Strategy rounds until the fire reaches the last node of the tree
+        let rounds: Vec<_> = (global_time..=max_arrival.max(global_time))
+            .map(|time| (time, settings.firefighters_at(time)))
+            .filter(|&(_, num_ffs)| num_ffs > 0)
+            .collect();
+        if !matches!(rounds.first(), Some(&(time, _)) if time == global_time) {
+            return;
+        }
+
+        let plan = if self.use_exact && tree.len() <= self.max_exact_nodes {
+            tree.optimal(&rounds).unwrap_or_else(|| tree.greedy(&rounds))
+        } else {
+            tree.greedy(&rounds)
+        };
+        node_data.mark_defended(&plan[0], global_time);
+    }
+}
+
+/// Lookahead fire containment strategy.
+/// Every round, candidate defense sets are derived from the choice of a cheap base policy, the
+/// nodes closest to the fire and random variations of both. Each candidate is defended in a
+/// silent copy of the simulation, which is then simulated a few rounds ahead with the base
+/// policy. The candidate with the fewest burned nodes or the least burned weight at the end is
+/// defended.
+#[derive(Debug)]
+pub struct RolloutStrategy {
+    graph: Arc<Graph>,
+    /// Policy that proposes the first candidate
+    base_policy: Box<OSMFStrategy>,
+    /// Separate instance of the base policy that contains the fire in the copies, so that the
+    /// state of the base policy is not reset by switching between the copies and the simulation
+    lookahead_policy: Box<OSMFStrategy>,
+    distances: IncrementalDistances,
+    num_candidates: usize,
+    /// Number of strategy rounds to simulate ahead
+    horizon: usize,
+}
+
+impl RolloutStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![
+            OSMFParameterSpec {
+                name: "base_policy",
+                description: "Strategy that contains the fire while simulating ahead",
+                param_type: OSMFParameterType::Choice { choices: vec!["Greedy", "Score"] },
+                default: OSMFParameterValue::Choice("Greedy".to_string()),
+            },
+            OSMFParameterSpec {
+                name: "num_candidates",
+                description: "Maximum number of candidate defense sets that are compared in \
+                each round",
+                param_type: OSMFParameterType::Integer { min: 1, max: 64 },
+                default: OSMFParameterValue::Integer(8),
+            },
+            OSMFParameterSpec {
+                name: "horizon",
+                description: "Number of strategy rounds that are simulated ahead",
+                param_type: OSMFParameterType::Integer { min: 1, max: 100 },
+                default: OSMFParameterValue::Integer(4),
+            },
+        ]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        *self.base_policy = OSMFStrategy::from_name_and_graph(params.choice("base_policy"),
+                                                              self.graph.clone()).unwrap();
+        *self.lookahead_policy = OSMFStrategy::from_name_and_graph(params.choice("base_policy"),
+                                                                   self.graph.clone()).unwrap();
+        self.num_candidates = params.integer("num_candidates") as usize;
+        self.horizon = params.integer("horizon") as usize;
+    }
+
+    /// Returns up to `num_candidates` distinct candidate defense sets of at most `budget` nodes
+    fn candidates(&mut self, settings: &OSMFSettings, node_data: &NodeDataStorage,
+                  global_time: TimeUnit, budget: usize) -> Vec<Vec<usize>> {
+        // Choice of the base policy
+        let mut engine = StepEngine::new(&self.graph, settings, node_data, global_time);
+        self.base_policy.execute(settings, engine.node_data_mut(), global_time);
+        let mut proposed = engine.node_data().get_defended_at(&global_time);
+        proposed.truncate(budget);
+        proposed.sort_unstable();
+
+        // Nodes closest to the fire
+        let sources: Vec<_> = node_data.get_burning().into_iter()
+            .map(|node_id| (node_id, 0))
+            .collect();
+        self.distances.update(&sources, node_data);
+        let pool = closest_undefended(&self.graph, settings, node_data, self.distances.dists(),
+                                      &[]);
+        let mut closest: Vec<_> = pool.iter().copied().take(budget).collect();
+        closest.sort_unstable();
+
+        let mut candidates = vec![proposed.clone()];
+        if !candidates.contains(&closest) {
+            candidates.push(closest.clone());
+        }
+
+        // Replace single nodes of both sets by other nodes close to the fire
+        let pool = &pool[..pool.len().min(2 * budget + self.num_candidates)];
+        let mut rng = StdRng::seed_from_u64(settings.seed.unwrap_or_default()
+            .wrapping_add(global_time));
+        for i in 0..4 * self.num_candidates {
+            if candidates.len() >= self.num_candidates {
+                break;
+            }
+            let mut candidate = if i % 2 == 0 { proposed.clone() } else { closest.clone() };
+            let replacement = match pool.choose(&mut rng) {
+                Some(&node_id) if !candidate.contains(&node_id) => node_id,
+                _ => continue,
+            };
+            if candidate.len() < budget {
+                candidate.push(replacement);
+            } else if let Some(node) = candidate.choose_mut(&mut rng) {
+                *node = replacement;
+            }
+            candidate.sort_unstable();
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+
+        candidates.truncate(self.num_candidates);
+        candidates
+    }
+
+    /// Get the number or weight of the nodes that burned after defending `candidate` and
+    /// simulating `horizon` strategy rounds ahead with the base policy
+    fn evaluate(&mut self, candidate: &[usize], settings: &OSMFSettings,
+                node_data: &NodeDataStorage, global_time: TimeUnit) -> f64 {
+        let mut engine = StepEngine::new(&self.graph, settings, node_data, global_time);
+        engine.defend(candidate);
+        engine.spread_fire();
+        let end_time = settings.round_after(global_time, self.horizon);
+        let lookahead_policy = &mut self.lookahead_policy;
+        while engine.is_active() && engine.time() < end_time {
+            engine.step(|settings, node_data, time| lookahead_policy.execute(settings, node_data,
+                                                                             time));
+        }
+        engine.burned_cost()
+    }
+}
+
+impl Default for RolloutStrategy {
+    fn default() -> Self {
+        Self::new(Arc::default())
+    }
+}
+
+impl Strategy for RolloutStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            base_policy: Box::new(OSMFStrategy::Greedy(GreedyStrategy::new(graph.clone()))),
+            lookahead_policy: Box::new(OSMFStrategy::Greedy(GreedyStrategy::new(graph.clone()))),
+            distances: IncrementalDistances::new(graph.clone()),
+            graph,
+            num_candidates: 8,
+            horizon: 4,
+        }
+    }
+
+    fn execute(&mut self, settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let budget = settings.firefighters_at(global_time);
+        let candidates = self.candidates(settings, node_data, global_time, budget);
+
+        let mut best: Option<(f64, Vec<usize>)> = None;
+        for candidate in candidates {
+            let cost = self.evaluate(&candidate, settings, node_data, global_time);
+            log::debug!("Rollout of {:?} in round {}: {}", &candidate, global_time, cost);
+            if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                best = Some((cost, candidate));
+            }
+        }
+
+        if let Some((_, to_defend)) = best {
+            node_data.mark_defended(&to_defend, global_time);
+        }
+    }
+}
+
+/// Offline fire containment strategy for planning studies.
+/// When initialized, simulated annealing optimizes an ordered list of nodes to defend within a
+/// budget of evaluations, such that the plan only depends on the seed of the simulation. A list is decoded by defending the next undefended nodes of the list in each
+/// strategy round and evaluated by simulating the whole fire in a silent copy of the
+/// simulation. The search starts from the defenses of `Greedy` and `SingleMinDistanceSet` and
+/// the defenses of the best list are executed as a static plan.
+#[derive(Debug, Default)]
+pub struct AnnealingStrategy {
+    graph: Arc<Graph>,
+    /// Optional wall-clock time budget, which makes the plan depend on the speed of the machine
+    time_budget_millis: Option<u128>,
+    max_evaluations: usize,
+    /// Offset of the seed of the random moves from the seed of the simulation
+    seed: u64,
+    roots: Vec<usize>,
+    /// Nodes to defend in each strategy round
+    plan: BTreeMap<TimeUnit, Vec<usize>>,
+}
+
+/// Outcome of simulating the whole fire with a defense policy
+struct PlanOutcome {
+    cost: f64,
+    end_time: TimeUnit,
+    /// Nodes defended in each strategy round
+    plan: BTreeMap<TimeUnit, Vec<usize>>,
+}
+
+impl AnnealingStrategy {
+    /// Returns the tunable parameters of this strategy
+    fn parameters() -> Vec<OSMFParameterSpec> {
+        vec![
+            OSMFParameterSpec {
+                name: "time_budget_millis",
+                description: "Wall-clock time in milliseconds that the search may take in addition \
+                to the limit of evaluations, or 0 for no time limit. A time limit makes the \
+                defense plan depend on the speed of the machine.",
+                param_type: OSMFParameterType::Integer { min: 0, max: 3_600_000 },
+                default: OSMFParameterValue::Integer(0),
+            },
+            OSMFParameterSpec {
+                name: "max_evaluations",
+                description: "Maximum number of defense lists that are simulated",
+                param_type: OSMFParameterType::Integer { min: 1, max: 10_000_000 },
+                default: OSMFParameterValue::Integer(10_000),
+            },
+            OSMFParameterSpec {
+                name: "seed",
+                description: "Offset of the seed of the random moves of the search from the seed \
+                of the simulation",
+                param_type: OSMFParameterType::Integer { min: 0, max: i64::MAX },
+                default: OSMFParameterValue::Integer(0),
+            },
+        ]
+    }
+
+    fn configure(&mut self, params: &OSMFParameters) {
+        self.time_budget_millis = Some(params.integer("time_budget_millis") as u128)
+            .filter(|&millis| millis > 0);
+        self.max_evaluations = params.integer("max_evaluations") as usize;
+        self.seed = params.integer("seed") as u64;
+    }
+
+    /// Get the computed defense plan
+    pub fn defense_plan(&self) -> OSMFDefensePlan {
+        OSMFDefensePlan {
+            roots: self.roots.clone(),
+            defenses: self.plan.iter()
+                .map(|(&time, nodes)| OSMFPlannedDefense { time, nodes: nodes.clone() })
+                .collect(),
+        }
+    }
+
+    /// Simulate the whole fire from the state in `node_data` at time `0` while `policy`
+    /// contains it. The simulation stops early once the cost exceeds `bound`.
+    fn simulate<F>(&self, settings: &OSMFSettings, node_data: &NodeDataStorage, bound: f64,
+                   mut policy: F) -> PlanOutcome
+        where F: FnMut(&OSMFSettings, &mut NodeDataStorage, TimeUnit) {
+        let mut engine = StepEngine::new(&self.graph, settings, node_data, 0);
+        let mut plan = BTreeMap::new();
+        while engine.is_active() && engine.burned_cost() <= bound && settings.termination.max_time
+            .is_none_or(|max_time| engine.time() < max_time) {
+            engine.step(|settings, node_data, time| {
+                policy(settings, node_data, time);
+                let mut defended = node_data.get_defended_at(&time);
+                defended.sort_unstable();
+                plan.insert(time, defended);
+            });
+        }
+        PlanOutcome {
+            cost: engine.burned_cost(),
+            end_time: engine.time(),
+            plan,
+        }
+    }
+
+    /// Simulate the whole fire while defending the nodes of `order` in the given order.
+    /// See `simulate`.
+    fn evaluate(&self, order: &[usize], settings: &OSMFSettings, node_data: &NodeDataStorage,
+                bound: f64) -> PlanOutcome {
+        let mut next = 0;
+        self.simulate(settings, node_data, bound, |settings, node_data, time| {
+            let budget = settings.firefighters_at(time);
+            let mut to_defend = Vec::with_capacity(budget);
+            while to_defend.len() < budget && next < order.len() {
+                if node_data.is_undefended(&order[next]) {
+                    to_defend.push(order[next]);
+                }
+                next += 1;
+            }
+            node_data.mark_defended(&to_defend, time);
+        })
+    }
+
+    /// Compute the defense plan for the fire roots in `roots`
+    pub(super) fn compute_plan(&mut self, roots: &[usize], settings: &OSMFSettings,
+                               node_data: &NodeDataStorage) {
+        let start = Instant::now();
+        self.roots = roots.to_vec();
+
+        // Start from the defenses of cheap strategies
+        let mut initial_orders = Vec::new();
+        for strategy_name in &["Greedy", "SingleMinDistanceSet"] {
+            let mut policy = OSMFStrategy::from_name_and_graph(strategy_name, self.graph.clone())
+                .unwrap();
+            policy.initialize(&roots.to_vec(), settings, node_data);
+            let outcome = self.simulate(settings, node_data, f64::INFINITY,
+                                        |settings, node_data, time|
+                policy.execute(settings, node_data, time));
+            let order: Vec<_> = outcome.plan.values().flatten().copied().collect();
+            initial_orders.push((outcome, order));
+        }
+        let end_time = initial_orders.iter()
+            .map(|(outcome, _)| outcome.end_time)
+            .max()
+            .unwrap_or_default();
+        let (mut best, mut order) = initial_orders.into_iter()
+            .min_by(|(a, _), (b, _)| a.cost.total_cmp(&b.cost))
+            .unwrap();
+        let initial_cost = best.cost;
+
+        // Lists contain all nodes that can be defended before the fire stops, chosen from the
+        // nodes closest to the roots
+        let length = settings.firefighters_by(end_time).max(order.len());
+        let (dists, _) = self.graph.run_dijkstra_with_filter(roots, |edge|
+            !node_data.is_defended(&edge.tgt));
+        let mut pool = closest_undefended(&self.graph, settings, node_data, &dists, &order);
+        pool.truncate(4 * length);
+        pool.extend(&order);
+        let mut in_order = vec![false; self.graph.num_nodes];
+        for &node_id in &order {
+            in_order[node_id] = true;
+        }
+        for &node_id in &pool {
+            if order.len() >= length {
+                break;
+            }
+            if !in_order[node_id] {
+                in_order[node_id] = true;
+                order.push(node_id);
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(settings.seed.unwrap_or_default()
+            .wrapping_add(self.seed));
+        let mut cost = best.cost;
+        let initial_temperature = (0.01 * best.cost).max(1.0);
+        let mut num_evaluations = 0;
+        while order.len() > 1 {
+            let progress = self.time_budget_millis
+                .map_or(0.0, |millis| start.elapsed().as_millis() as f64 / millis as f64)
+                .max(num_evaluations as f64 / self.max_evaluations as f64);
+            if progress >= 1.0 {
+                break;
+            }
+            let temperature = initial_temperature * (1.0 - progress);
+
+            // Swap two nodes, move a node or replace a node by another node of the pool
+            let mut neighbor = order.clone();
+            let i = rng.gen_range(0..neighbor.len());
+            let j = rng.gen_range(0..neighbor.len());
+            let mut replaced = None;
+            match rng.gen_range(0..3) {
+                0 => neighbor.swap(i, j),
+                1 => {
+                    let node_id = neighbor.remove(i);
+                    neighbor.insert(j, node_id);
+                }
+                _ => match pool.choose(&mut rng) {
+                    Some(&node_id) if !in_order[node_id] => {
+                        replaced = Some((neighbor[i], node_id));
+                        neighbor[i] = node_id;
+                    }
+                    _ => continue,
+                },
+            }
+
+            // Lists that burn much more than the current list are never accepted, so their
+            // simulation stops early
+            let bound = cost + 20.0 * temperature;
+            let outcome = self.evaluate(&neighbor, settings, node_data, bound);
+            num_evaluations += 1;
+            if outcome.cost <= cost
+                || rng.gen::<f64>() < ((cost - outcome.cost) / temperature).exp() {
+                if let Some((removed, added)) = replaced {
+                    in_order[removed] = false;
+                    in_order[added] = true;
+                }
+                order = neighbor;
+                cost = outcome.cost;
+            }
+            if outcome.cost < best.cost {
+                best = outcome;
+            }
+        }
+
+        self.plan = best.plan;
+
+        log::info!("Computed annealing defense plan. cost={}, initial_cost={}, evaluations={}, \
+        millis={}.", best.cost, initial_cost, num_evaluations, start.elapsed().as_millis());
+    }
+}
+
+impl Strategy for AnnealingStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+            time_budget_millis: None,
+            max_evaluations: 10_000,
+            seed: 0,
+            roots: Vec::new(),
+            plan: BTreeMap::new(),
+        }
+    }
+
+    fn execute(&mut self, _settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let to_defend: Vec<_> = self.plan.get(&global_time).into_iter().flatten()
+            .filter(|&node_id| node_data.is_undefended(node_id))
+            .copied()
+            .collect();
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+/// Maximum number of partial defense sequences that the exact strategy explores before it
+/// settles for the best defense sequence found so far
+const EXACT_SEARCH_LIMIT: usize = 20_000;
+/// Maximum number of nodes of graphs on which the exact strategy may be used, as every explored
+/// defense sequence takes shortest path searches on the whole graph
+pub const EXACT_NODE_LIMIT: usize = 1000;
+
+/// Exact fire containment strategy for small instances.
+/// When initialized, a branch and bound search computes the defense sequence that minimizes the
+/// number or weight of burned nodes for the given roots and firefighter schedule.
+/// The sequence is optimal for the deterministic spread model without wind and secondary
+/// ignitions if the search finished within its limit. Graphs with more than `EXACT_NODE_LIMIT`
+/// nodes are rejected.
+#[derive(Debug, Default)]
+pub struct ExactStrategy {
+    graph: Arc<Graph>,
+    /// Nodes to defend in each strategy round
+    plan: BTreeMap<TimeUnit, Vec<usize>>,
+    optimal: bool,
+    lower_bound: f64,
+}
+
+impl ExactStrategy {
+    fn configure(&mut self, _params: &OSMFParameters) -> Result<(), OSMFSettingsError> {
+        if self.graph.num_nodes > EXACT_NODE_LIMIT {
+            return Err(OSMFSettingsError::InvalidStrategyParameter {
+                strategy_name: "Exact".to_string(),
+                reason: format!("the exact strategy is limited to graphs with at most {} nodes: {}",
+                                EXACT_NODE_LIMIT, self.graph.num_nodes),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns true if the computed defense sequence is proven to be optimal
+    pub fn is_optimal(&self) -> bool {
+        self.optimal
+    }
+
+    /// Get a lower bound on the number or weight of the nodes that burn under any defense
+    /// sequence
+    pub fn lower_bound(&self) -> f64 {
+        self.lower_bound
+    }
+
+    /// Compute the defense sequence for the fire roots in `roots`
+    pub(super) fn compute_plan(&mut self, roots: &[usize], settings: &OSMFSettings,
+                               node_data: &NodeDataStorage) {
+        let graph = &self.graph;
+        let mut search = ExactSearch {
+            graph,
+            roots,
+            costs: (0..graph.num_nodes)
+                .map(|node_id| match settings.objective {
+                    OSMFObjective::SavedNodes => 1.0,
+                    OSMFObjective::SavedWeight => graph.get_node_weight(node_id),
+                })
+                .collect(),
+            rounds: Vec::new(),
+            burn_duration: settings.burn_duration,
+            removed: (0..graph.num_nodes)
+                .map(|node_id| node_data.is_defended(&node_id))
+                .collect(),
+            sequence: Vec::new(),
+            best_cost: f64::INFINITY,
+            best_sequence: Vec::new(),
+            root_bound: 0.0,
+            num_visited: 0,
+            complete: true,
+        };
+
+        // Defending nodes is pointless once the fire stopped without any defenses
+        let (arrivals, _) = search.arrivals();
+        let horizon = arrivals.iter()
+            .filter(|&&arrival| arrival < usize::MAX)
+            .max()
+            .map_or(0, |&arrival| arrival as TimeUnit);
+        search.rounds = (1..=horizon)
+            .map(|time| (time, settings.firefighters_at(time)))
+            .filter(|&(_, num_ffs)| num_ffs > 0)
+            .collect();
+        let (arrivals, preds) = search.arrivals();
+        search.root_bound = search.lower_bound(0, 0, &arrivals, &preds);
+
+        search.search_round(0);
+
+        self.optimal = search.complete || search.best_cost <= search.root_bound;
+        self.lower_bound = if self.optimal { search.best_cost } else { search.root_bound };
+        self.plan = BTreeMap::new();
+        for &(time, node_id) in &search.best_sequence {
+            self.plan.entry(time).or_default().push(node_id);
+        }
+
+        log::info!("Computed exact defense sequence. cost={}, optimal={}, lower_bound={}, \
+        visited={}.", search.best_cost, self.optimal, self.lower_bound, search.num_visited);
+    }
+}
+
+impl Strategy for ExactStrategy {
+    fn new(graph: Arc<Graph>) -> Self {
+        Self {
+            graph,
+            plan: BTreeMap::new(),
+            optimal: false,
+            lower_bound: 0.0,
+        }
+    }
+
+    fn execute(&mut self, _settings: &OSMFSettings, node_data: &mut NodeDataStorage, global_time: TimeUnit) {
+        let to_defend: Vec<_> = self.plan.remove(&global_time).unwrap_or_default().into_iter()
+            .filter(|node_id| node_data.is_undefended(node_id))
+            .collect();
+        node_data.mark_defended(&to_defend, global_time);
+    }
+}
+
+/// Branch and bound search over the defense sequences of the exact fire containment strategy.
+/// Defending more nodes never lets more nodes burn and nodes can always be defended in an
+/// earlier round, so only sequences that use all firefighters of each round are explored.
+/// Nodes defended in the same round are chosen in the order of the candidate list.
+struct ExactSearch<'a> {
+    graph: &'a Graph,
+    roots: &'a [usize],
+    /// Cost of each node when it burns
+    costs: Vec<f64>,
+    /// Strategy rounds given by their time and number of firefighters
+    rounds: Vec<(TimeUnit, usize)>,
+    burn_duration: Option<TimeUnit>,
+    /// Nodes that were defended so far
+    removed: Vec<bool>,
+    sequence: Vec<(TimeUnit, usize)>,
+    best_cost: f64,
+    best_sequence: Vec<(TimeUnit, usize)>,
+    /// Lower bound on the cost of all defense sequences
+    root_bound: f64,
+    num_visited: usize,
+    complete: bool,
+}
+
+impl ExactSearch<'_> {
+    /// Compute the times at which the fire reaches each node and the predecessors on the
+    /// shortest paths if the nodes defended so far are removed
+    fn arrivals(&self) -> (Vec<usize>, Vec<usize>) {
+        let removed = &self.removed;
+        let burn_duration = self.burn_duration;
+        self.graph.run_dijkstra_with_filter(self.roots, |edge|
+            !removed[edge.tgt] && burn_duration.is_none_or(|d| (edge.dist as TimeUnit) < d))
+    }
+
+    /// Returns true if the search has to stop because it reached its limit or found a defense
+    /// sequence that matches the lower bound. Otherwise, the next partial defense sequence is
+    /// counted as visited.
+    fn visit(&mut self) -> bool {
+        if self.best_cost <= self.root_bound {
+            return true;
+        }
+        if self.num_visited >= EXACT_SEARCH_LIMIT {
+            self.complete = false;
+            return true;
+        }
+        self.num_visited += 1;
+        false
+    }
+
+    /// Explore all defense sequences that continue the current sequence in round `round`
+    fn search_round(&mut self, round: usize) {
+        let (arrivals, preds) = self.arrivals();
+        let cost: f64 = arrivals.iter()
+            .enumerate()
+            .filter(|&(_, &arrival)| arrival < usize::MAX)
+            .map(|(node_id, _)| self.costs[node_id])
+            .sum();
+        if cost < self.best_cost {
+            self.best_cost = cost;
+            self.best_sequence = self.sequence.clone();
+        }
+
+        let Some(&(time, num_ffs)) = self.rounds.get(round) else {
+            return;
+        };
+        if self.visit() {
+            return;
+        }
+
+        // Nodes that the fire reaches, but that are not burning yet
+        let mut candidates: Vec<_> = (0..self.graph.num_nodes)
+            .filter(|&node_id| !self.removed[node_id] && arrivals[node_id] < usize::MAX
+                && arrivals[node_id] as TimeUnit >= time)
+            .collect();
+        if candidates.is_empty() || self.lower_bound(round, 0, &arrivals, &preds) >= self.best_cost {
+            return;
+        }
+
+        // Try the nodes that the fire reaches first and that shield the most nodes from the
+        // fire first
+        let order = shortest_path_order(&arrivals, &preds);
+        let shielded = self.subtree_costs(&order, &arrivals, &preds, usize::MAX);
+        candidates.sort_by(|&n1, &n2| arrivals[n1].cmp(&arrivals[n2])
+            .then_with(|| shielded[n2].total_cmp(&shielded[n1])));
+
+        let num_to_defend = min(num_ffs, candidates.len());
+        self.search_defenses(round, &candidates, 0, num_to_defend);
+    }
+
+    /// Explore all choices of `remaining` further nodes from `candidates[start..]` to defend in
+    /// round `round`
+    fn search_defenses(&mut self, round: usize, candidates: &[usize], start: usize,
+                       remaining: usize) {
+        let (time, num_ffs) = self.rounds[round];
+        for i in start..=(candidates.len() - remaining) {
+            let node_id = candidates[i];
+            self.removed[node_id] = true;
+            self.sequence.push((time, node_id));
+            if remaining == 1 {
+                self.search_round(round + 1);
+            } else if !self.visit() {
+                let (arrivals, preds) = self.arrivals();
+                let used = min(num_ffs, candidates.len()) - remaining + 1;
+                if self.lower_bound(round, used, &arrivals, &preds) < self.best_cost {
+                    self.search_defenses(round, candidates, i + 1, remaining - 1);
+                }
+            }
+            self.sequence.pop();
+            self.removed[node_id] = false;
+            if self.best_cost <= self.root_bound || !self.complete {
+                return;
+            }
+        }
+    }
+
+    /// For each node, compute the cost of the nodes reached by the fire before `horizon` whose
+    /// shortest paths from the fire roots lead through the node. `order` contains all nodes
+    /// reached by the fire such that predecessors precede their successors.
+    fn subtree_costs(&self, order: &[usize], arrivals: &[usize], preds: &[usize],
+                     horizon: usize) -> Vec<f64> {
+        let mut costs: Vec<_> = (0..self.graph.num_nodes)
+            .map(|node_id| if arrivals[node_id] < horizon { self.costs[node_id] } else { 0.0 })
+            .collect();
+        for &node_id in order.iter().rev() {
+            let pred = preds[node_id];
+            if pred < usize::MAX {
+                costs[pred] += costs[node_id];
+            }
+        }
+        costs
+    }
+
+    /// Compute a lower bound on the cost of all defense sequences that continue the current
+    /// sequence in round `round`. Nodes that the fire reaches before a round can only be saved
+    /// by defending a node on their shortest paths in an earlier round.
+    /// `used` firefighters of round `round` already defended nodes.
+    /// The bound is evaluated for the rounds `round`, `round + 1`, `round + 3`, ... and the end.
+    fn lower_bound(&self, round: usize, used: usize, arrivals: &[usize], preds: &[usize]) -> f64 {
+        let time = self.rounds.get(round).map_or(usize::MAX, |&(time, _)| time as usize);
+        let candidates: Vec<_> = (0..self.graph.num_nodes)
+            .filter(|&node_id| !self.removed[node_id] && arrivals[node_id] < usize::MAX
+                && arrivals[node_id] >= time)
+            .collect();
+        let max_arrival = arrivals.iter()
+            .copied()
+            .filter(|&arrival| arrival < usize::MAX)
+            .max()
+            .unwrap_or(0);
+        let threatened_by = |horizon: usize| arrivals.iter()
+            .enumerate()
+            .filter(|&(_, &arrival)| arrival < horizon)
+            .map(|(node_id, _)| self.costs[node_id])
+            .sum::<f64>();
+
+        let order = shortest_path_order(arrivals, preds);
+        let mut bound = threatened_by(time);
+        let mut step = 1;
+        let mut k = round;
+        while k < self.rounds.len() {
+            let budget: usize = self.rounds[round..=k].iter()
+                .map(|&(_, num_ffs)| num_ffs)
+                .sum::<usize>() - used;
+            let horizon = self.rounds.get(k + 1)
+                .map_or(usize::MAX, |&(time, _)| time as usize);
+            let shielded = self.subtree_costs(&order, arrivals, preds, horizon);
+            let mut savings: Vec<_> = candidates.iter()
+                .map(|&node_id| shielded[node_id])
+                .collect();
+            savings.sort_unstable_by(|s1, s2| s2.total_cmp(s1));
+            let max_savings: f64 = savings.iter().take(budget).sum();
+            bound = bound.max(threatened_by(horizon) - max_savings);
+            if horizon > max_arrival {
+                break;
+            }
+            k = min(k + step, self.rounds.len() - 1).max(k + 1);
+            step *= 2;
+        }
+        bound
+    }
+}
+
+/// Order all nodes reached by the fire such that the predecessors on their shortest paths
+/// from the fire roots precede them
+fn shortest_path_order(arrivals: &[usize], preds: &[usize]) -> Vec<usize> {
+    let mut successors = vec![Vec::new(); arrivals.len()];
+    let mut order = Vec::new();
+    for (node_id, &pred) in preds.iter().enumerate() {
+        if pred < usize::MAX {
+            successors[pred].push(node_id);
+        } else if arrivals[node_id] < usize::MAX {
+            order.push(node_id);
+        }
+    }
+    let mut i = 0;
+    while i < order.len() {
+        order.extend_from_slice(&successors[order[i]]);
+        i += 1;
+    }
+    order
+}
+
 /// Random fire containment strategy
 #[derive(Debug, Default)]
 pub struct RandomStrategy {
@@ -688,8 +2305,9 @@ impl Strategy for RandomStrategy {
             .map(|node| node.id)
             .collect();
 
-        let num_to_defend = min(settings.num_ffs, nodes_to_defend.len());
-        let mut rng = thread_rng();
+        let num_to_defend = min(settings.firefighters_at(global_time), nodes_to_defend.len());
+        let mut rng = StdRng::seed_from_u64(settings.seed.unwrap_or_default()
+            .wrapping_add(global_time));
         let to_defend: Vec<_> = nodes_to_defend
             .choose_multiple(&mut rng, num_to_defend)
             .cloned()
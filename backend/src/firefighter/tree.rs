@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use crate::firefighter::problem::{NodeDataStorage, OSMFObjective, OSMFSettings};
+use crate::firefighter::TimeUnit;
+use crate::graph::Graph;
+
+/// Maximum number of partial solutions that the dynamic program stores before it gives up
+const EXACT_STATE_LIMIT: usize = 200_000;
+
+/// Forest in which the fire spreads from the roots towards the leaves, such that defending a
+/// node saves all nodes in its subtree.
+/// Nodes are stored in preorder, so the subtree of the node at index `i` consists of the nodes
+/// at the indices `i..i + subtree_sizes[i]`.
+#[derive(Debug)]
+pub(super) struct FireTree {
+    /// Id of each tree node in the graph
+    node_ids: Vec<usize>,
+    subtree_sizes: Vec<usize>,
+    /// Number or weight of the nodes in each subtree
+    subtree_costs: Vec<f64>,
+    /// Time at which the fire reaches each node
+    arrivals: Vec<TimeUnit>,
+}
+
+impl FireTree {
+    /// Create a new tree of the nodes with ids `node_ids` from the index of the parent of each
+    /// node in `parents`, which is `usize::MAX` for nodes without parent, and the cost and the
+    /// arrival time of the fire of each node
+    pub(super) fn new(node_ids: &[usize], parents: &[usize], costs: &[f64],
+                      arrivals: &[TimeUnit]) -> Self {
+        let num_nodes = node_ids.len();
+        let mut children = vec![Vec::new(); num_nodes];
+        let mut tops = Vec::new();
+        for (i, &parent) in parents.iter().enumerate() {
+            if parent < num_nodes {
+                children[parent].push(i);
+            } else {
+                tops.push(i);
+            }
+        }
+
+        // Number the nodes in preorder and sum up the costs of all subtrees
+        let mut preorder = Vec::with_capacity(num_nodes);
+        let mut stack: Vec<_> = tops.into_iter().rev().collect();
+        while let Some(i) = stack.pop() {
+            preorder.push(i);
+            stack.extend(children[i].iter().rev());
+        }
+        let mut position = vec![0; num_nodes];
+        for (pos, &i) in preorder.iter().enumerate() {
+            position[i] = pos;
+        }
+        let mut subtree_sizes = vec![1; num_nodes];
+        let mut subtree_costs: Vec<_> = preorder.iter().map(|&i| costs[i]).collect();
+        for &i in preorder.iter().rev() {
+            if parents[i] < num_nodes {
+                let (pos, parent_pos) = (position[i], position[parents[i]]);
+                subtree_sizes[parent_pos] += subtree_sizes[pos];
+                subtree_costs[parent_pos] += subtree_costs[pos];
+            }
+        }
+
+        Self {
+            node_ids: preorder.iter().map(|&i| node_ids[i]).collect(),
+            subtree_sizes,
+            subtree_costs,
+            arrivals: preorder.iter().map(|&i| arrivals[i]).collect(),
+        }
+    }
+
+    /// Build the shortest path tree of the undefended nodes from the burning nodes in
+    /// `node_data` that still spread the fire at time `time`, which is how the fire spreads in
+    /// the deterministic spread model without wind
+    pub(super) fn shortest_path_tree(graph: &Graph, settings: &OSMFSettings,
+                                     node_data: &NodeDataStorage, time: TimeUnit) -> Self {
+        let sources: Vec<_> = node_data.get_burning().into_iter()
+            .filter(|node_id| !node_data.is_burned_out_by(node_id, &time))
+            .map(|node_id| (node_id, node_data.get_burning_time(&node_id).unwrap() as usize))
+            .collect();
+        let (arrivals, preds) = graph.run_dijkstra_with_offsets_and_filter(&sources, |edge|
+            node_data.is_undefended(&edge.tgt));
+
+        let node_ids: Vec<_> = (0..graph.num_nodes)
+            .filter(|&node_id| node_data.is_undefended(&node_id) && arrivals[node_id] < usize::MAX)
+            .collect();
+        let mut index = vec![usize::MAX; graph.num_nodes];
+        for (i, &node_id) in node_ids.iter().enumerate() {
+            index[node_id] = i;
+        }
+        let parents: Vec<_> = node_ids.iter()
+            .map(|&node_id| preds[node_id])
+            .map(|pred| if pred < usize::MAX { index[pred] } else { usize::MAX })
+            .collect();
+        let costs: Vec<_> = node_ids.iter()
+            .map(|&node_id| match settings.objective {
+                OSMFObjective::SavedNodes => 1.0,
+                OSMFObjective::SavedWeight => graph.get_node_weight(node_id),
+            })
+            .collect();
+        let arrivals: Vec<_> = node_ids.iter()
+            .map(|&node_id| arrivals[node_id] as TimeUnit)
+            .collect();
+
+        Self::new(&node_ids, &parents, &costs, &arrivals)
+    }
+
+    /// Get the number of nodes in this tree
+    pub(super) fn len(&self) -> usize {
+        self.node_ids.len()
+    }
+
+    /// Get the latest time at which the fire reaches a node of this tree
+    pub(super) fn max_arrival(&self) -> Option<TimeUnit> {
+        self.arrivals.iter().copied().max()
+    }
+
+    /// Get the index of the last strategy round in `rounds` in which each node can be defended
+    /// before the fire reaches it
+    fn deadlines(&self, rounds: &[(TimeUnit, usize)]) -> Vec<Option<usize>> {
+        self.arrivals.iter()
+            .map(|&arrival| rounds.iter().rposition(|&(time, _)| time <= arrival))
+            .collect()
+    }
+
+    /// Compute a defense plan for the strategy rounds in `rounds`, which are given by their time
+    /// and number of firefighters, with the classic greedy algorithm: every round, the nodes
+    /// with the heaviest subtrees among the nodes that the fire reaches first are defended.
+    /// On trees with a single root and one firefighter per round, this saves at least half as
+    /// much as an optimal plan.
+    /// Returns the ids of the nodes to defend in each round.
+    pub(super) fn greedy(&self, rounds: &[(TimeUnit, usize)]) -> Vec<Vec<usize>> {
+        let deadlines = self.deadlines(rounds);
+        let mut candidates: Vec<_> = (0..self.len())
+            .filter(|&i| deadlines[i].is_some())
+            .collect();
+        candidates.sort_by(|&i, &j| deadlines[i].cmp(&deadlines[j])
+            .then_with(|| self.subtree_costs[j].total_cmp(&self.subtree_costs[i]))
+            .then_with(|| i.cmp(&j)));
+
+        let mut is_saved = vec![false; self.len()];
+        let mut plan = Vec::with_capacity(rounds.len());
+        for (round, &(_, num_ffs)) in rounds.iter().enumerate() {
+            let mut to_defend = Vec::with_capacity(num_ffs);
+            for &i in &candidates {
+                if to_defend.len() == num_ffs {
+                    break;
+                }
+                if is_saved[i] || deadlines[i] < Some(round) {
+                    continue;
+                }
+                is_saved[i..i + self.subtree_sizes[i]].iter_mut().for_each(|saved| *saved = true);
+                to_defend.push(self.node_ids[i]);
+            }
+            plan.push(to_defend);
+        }
+        plan
+    }
+
+    /// Compute an optimal defense plan for the strategy rounds in `rounds`, which are given by
+    /// their time and number of firefighters, with a dynamic program over the nodes in preorder
+    /// that either defends a node and skips its subtree or does not defend it. Partial
+    /// solutions are distinguished by the number of defended nodes per last possible round.
+    /// Returns the ids of the nodes to defend in each round or `None` if the tree is too large.
+    pub(super) fn optimal(&self, rounds: &[(TimeUnit, usize)]) -> Option<Vec<Vec<usize>>> {
+        let deadlines = self.deadlines(rounds);
+        let num_rounds = deadlines.iter().flatten().max().map_or(0, |&round| round + 1);
+        let capacities: Vec<_> = rounds[..num_rounds].iter()
+            .scan(0, |total, &(_, num_ffs)| {
+                *total += num_ffs;
+                Some(*total)
+            })
+            .collect();
+
+        // Partial solutions are stored with the solution they extend and the node they defend
+        struct Entry {
+            cost: f64,
+            prev: usize,
+            defended: Option<usize>,
+        }
+        let mut entries = vec![Entry { cost: 0.0, prev: usize::MAX, defended: None }];
+        let mut layers: Vec<HashMap<Vec<usize>, usize>> = vec![HashMap::new(); self.len() + 1];
+        layers[0].insert(vec![0; num_rounds], 0);
+
+        for i in 0..self.len() {
+            let mut layer: Vec<_> = std::mem::take(&mut layers[i]).into_iter().collect();
+            layer.sort_unstable();
+            for (counts, entry) in layer {
+                let cost = entries[entry].cost;
+                let mut extensions = Vec::with_capacity(2);
+                if let Some(deadline) = deadlines[i] {
+                    // Nodes can be defended in time iff at most `capacities[k]` of them must be
+                    // defended by round `k` for all rounds `k`
+                    let mut defended = counts.clone();
+                    defended[deadline] += 1;
+                    let is_feasible = defended.iter()
+                        .scan(0, |total, &count| {
+                            *total += count;
+                            Some(*total)
+                        })
+                        .zip(&capacities)
+                        .all(|(total, &capacity)| total <= capacity);
+                    if is_feasible {
+                        extensions.push((i + self.subtree_sizes[i], defended,
+                                         cost + self.subtree_costs[i], Some(i)));
+                    }
+                }
+                extensions.push((i + 1, counts, cost, None));
+
+                for (next, counts, cost, defended) in extensions {
+                    match layers[next].get(&counts) {
+                        Some(&other) if entries[other].cost >= cost => continue,
+                        _ => {
+                            layers[next].insert(counts, entries.len());
+                            entries.push(Entry { cost, prev: entry, defended });
+                        }
+                    }
+                }
+                if entries.len() > EXACT_STATE_LIMIT {
+                    return None;
+                }
+            }
+        }
+
+        // Schedule the defended nodes of the best solution by their last possible round.
+        // Ties are broken by the defended nodes per round, so that the plan does not depend on
+        // the iteration order of the layer.
+        let mut entry = layers[self.len()].iter()
+            .max_by(|(counts1, &e1), (counts2, &e2)| entries[e1].cost
+                .total_cmp(&entries[e2].cost)
+                .then_with(|| counts2.cmp(counts1)))
+            .map(|(_, &entry)| entry)?;
+        let mut defended = Vec::new();
+        while entry != usize::MAX {
+            defended.extend(entries[entry].defended);
+            entry = entries[entry].prev;
+        }
+        defended.sort_by_key(|&i| (deadlines[i], i));
+        let mut defended = defended.into_iter();
+        Some(rounds.iter()
+            .map(|&(_, num_ffs)| defended.by_ref()
+                .take(num_ffs)
+                .map(|i| self.node_ids[i])
+                .collect())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::prelude::*;
+
+    use crate::firefighter::tree::FireTree;
+
+    /// Returns the number or weight of the nodes saved by defending the nodes in `nodes`
+    fn saved_cost(tree: &FireTree, nodes: &[usize]) -> f64 {
+        let mut cost = 0.0;
+        let mut i = 0;
+        while i < tree.len() {
+            if nodes.contains(&tree.node_ids[i]) {
+                cost += tree.subtree_costs[i];
+                i += tree.subtree_sizes[i];
+            } else {
+                i += 1;
+            }
+        }
+        cost
+    }
+
+    /// Returns the largest cost that any feasible set of defended nodes saves
+    fn brute_force(tree: &FireTree, rounds: &[(u64, usize)]) -> f64 {
+        let deadlines = tree.deadlines(rounds);
+        let mut best: f64 = 0.0;
+        for subset in 0..1u32 << tree.len() {
+            let nodes: Vec<_> = (0..tree.len()).filter(|&i| subset & 1 << i != 0).collect();
+            let is_antichain = nodes.iter().all(|&i| nodes.iter()
+                .all(|&j| j <= i || j >= i + tree.subtree_sizes[i]));
+            let is_feasible = nodes.iter().all(|&i| deadlines[i].is_some())
+                && (0..rounds.len()).all(|round| {
+                    let num_due = nodes.iter().filter(|&&i| deadlines[i] <= Some(round)).count();
+                    num_due <= rounds[..=round].iter().map(|&(_, num_ffs)| num_ffs).sum()
+                });
+            if is_antichain && is_feasible {
+                best = best.max(nodes.iter().map(|&i| tree.subtree_costs[i]).sum());
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_fire_tree() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            // Random tree with a single root that the fire reaches one level per time step
+            let num_nodes = rng.gen_range(1..=12);
+            let mut parents = vec![usize::MAX];
+            let mut depths = vec![0];
+            for i in 1..num_nodes {
+                let parent = rng.gen_range(0..i);
+                parents.push(parent);
+                depths.push(depths[parent] + 1);
+            }
+            let node_ids: Vec<_> = (100..100 + num_nodes).collect();
+            let costs: Vec<_> = (0..num_nodes).map(|_| rng.gen_range(1..4) as f64).collect();
+            let tree = FireTree::new(&node_ids, &parents, &costs, &depths);
+            assert_eq!(tree.subtree_sizes[0], num_nodes);
+            assert_eq!(tree.subtree_costs[0], costs.iter().sum::<f64>());
+
+            let num_ffs = rng.gen_range(1..=2);
+            let rounds: Vec<_> = (1..=tree.max_arrival().unwrap()).map(|time| (time, num_ffs)).collect();
+            let optimum = brute_force(&tree, &rounds);
+
+            let plan = tree.optimal(&rounds).unwrap();
+            assert!(plan.iter().zip(&rounds).all(|(nodes, &(_, num_ffs))| nodes.len() <= num_ffs));
+            let defended: Vec<_> = plan.concat();
+            assert_eq!(saved_cost(&tree, &defended), optimum);
+
+            let plan = tree.greedy(&rounds);
+            let saved = saved_cost(&tree, &plan.concat());
+            assert!(saved <= optimum);
+            if num_ffs == 1 {
+                assert!(2.0 * saved >= optimum);
+            }
+        }
+    }
+}
@@ -27,8 +27,13 @@ impl Color {
     const DARK_GREY: &'static Color = &Color { rgb: Rgb([64, 64, 64]), layer: 0 };
     const WHITE: &'static Color = &Color { rgb: Rgb([255, 255, 255]), layer: 1 };
     const RED: &'static Color = &Color { rgb: Rgb([255, 0, 0]), layer: 2 };
+    const DARK_RED: &'static Color = &Color { rgb: Rgb([128, 0, 0]), layer: 2 };
     const BLUE: &'static Color = &Color { rgb: Rgb([0, 0, 255]), layer: 3 };
+    const CYAN: &'static Color = &Color { rgb: Rgb([0, 255, 255]), layer: 3 };
     const YELLOW: &'static Color = &Color { rgb: Rgb([255, 255, 0]), layer: 4 };
+    const MAGENTA: &'static Color = &Color { rgb: Rgb([255, 0, 255]), layer: 4 };
+    const GREEN: &'static Color = &Color { rgb: Rgb([0, 200, 0]), layer: 5 };
+    const ORANGE: &'static Color = &Color { rgb: Rgb([255, 165, 0]), layer: 1 };
 }
 
 impl Eq for Color {}
@@ -169,6 +174,15 @@ impl View {
             let src = self.graph.get_node(edge.src);
             let tgt = self.graph.get_node(edge.tgt);
 
+            // Protected edges are drawn in blue if they are blocked in both directions and
+            // in orange if they are oriented
+            let col_edge = match (node_data.is_blocked_by(&edge.src, &edge.tgt, time),
+                                  node_data.is_blocked_by(&edge.tgt, &edge.src, time)) {
+                (true, true) => Color::BLUE,
+                (false, false) => Color::WHITE,
+                _ => Color::ORANGE,
+            };
+
             let mut w_px = ((src.lon - gb.min_lon) / deg_per_px_hz) as i64;
             let mut h_px = ((src.lat - gb.min_lat) / deg_per_px_vert) as i64;
 
@@ -273,7 +287,7 @@ impl View {
                     continue;
                 }
 
-                self.img_buf.put_pixel(w_px as u32, (h_max - h_px) as u32, Color::WHITE.rgb);
+                self.img_buf.put_pixel(w_px as u32, (h_max - h_px) as u32, col_edge.rgb);
             }
         }
 
@@ -284,11 +298,18 @@ impl View {
                 let w_px = ((node.lon - gb.min_lon) / deg_per_px_hz) as i64;
                 let h_px = ((node.lat - gb.min_lat) / deg_per_px_vert) as i64;
 
+                // Burned out nodes are shown as such even if the fire started at them
                 let col_px;
-                if node_data.is_root(&node.id) {
+                if node_data.is_burned_out_by(&node.id, time) {
+                    col_px = Color::DARK_RED;
+                } else if node_data.is_root(&node.id) {
                     col_px = Color::YELLOW;
+                } else if node_data.is_late_ignition(&node.id) && node_data.is_burning_by(&node.id, time) {
+                    col_px = Color::MAGENTA;
                 } else if node_data.is_burning_by(&node.id, time) {
                     col_px = Color::RED;
+                } else if node_data.is_firebreak(&node.id) {
+                    col_px = Color::CYAN;
                 } else if node_data.is_defended_by(&node.id, time) {
                     col_px = Color::BLUE;
                 } else {
@@ -308,6 +329,29 @@ impl View {
                 }
             }
         }
+        // For every firefighter, compute a square around the pixel of its position and color it
+        for ff in node_data.firefighters() {
+            let (last, next, progress) = ff.position_at(time);
+            let last = self.graph.get_node(last);
+            let next = self.graph.get_node(next);
+            let lat = last.lat + (next.lat - last.lat) * progress;
+            let lon = last.lon + (next.lon - last.lon) * progress;
+            if lat < gb.min_lat || lat > gb.max_lat || lon < gb.min_lon || lon > gb.max_lon {
+                continue;
+            }
+
+            let w_px = ((lon - gb.min_lon) / deg_per_px_hz) as i64;
+            let h_px = ((lat - gb.min_lat) / deg_per_px_vert) as i64;
+
+            let r = ((h_max.min(w_max)+1) as f64 * z.log(4.0).max(1.0) / 300.0) as i64;
+            pxs_to_draw.reserve((4 * r * r) as usize);
+            for w in (w_px-r).max(0)..=(w_px+r).min(w_max) {
+                for h in (h_px-r).max(0)..=(h_px+r).min(h_max) {
+                    pxs_to_draw.push((w as u32, h as u32, Color::GREEN));
+                }
+            }
+        }
+
         pxs_to_draw.sort_unstable_by(|(_, _, col1), (_, _, col2)| col1.cmp(&col2));
         for (w, h, col) in pxs_to_draw {
             self.img_buf.put_pixel(w, h_max as u32 - h, col.rgb);
@@ -1,15 +1,21 @@
 use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Formatter;
 use std::fs::File;
 use std::io::{prelude::*, BufReader};
 use std::num::{ParseIntError, ParseFloatError};
+use std::sync::{Arc, Mutex};
 
+use once_cell::sync::OnceCell;
 use serde::Serialize;
 
 use crate::binary_minheap::BinaryMinHeap;
+use crate::centrality::{self, Centrality};
 
 /// Type alias for the result of a run of the Dijkstra algorithm
 type DijkstraResult = Vec<usize>;
+/// Type alias for the cache entry of a centrality, which is filled by the first request
+type CentralityCell = Arc<OnceCell<Arc<Vec<f64>>>>;
 
 /// Struct to hold the grid bounds of a graph or part of a graph
 #[derive(Debug, Serialize)]
@@ -45,11 +51,13 @@ pub(crate) enum CompassDirection {
 ///
 /// # Attributes
 /// * `id` - An id uniquely identifying the node
+/// * `osm_id` - The id of the node in OpenStreetMap
 /// * `lat` - The nodes latitude coordinate
 /// * `lon` - The nodes longitude coordinate
 #[derive(Debug, Serialize, Default)]
 pub struct Node {
     pub id: usize,
+    pub osm_id: u64,
     pub lat: f64,
     pub lon: f64,
 }
@@ -83,6 +91,18 @@ impl Node {
             CompassDirection::Zero
         }
     }
+
+    /// Get the initial bearing from this node to `other` in degrees clockwise from north,
+    /// i.e. a value in `[0, 360)`
+    pub fn bearing_to(&self, other: &Node) -> f64 {
+        let lat1 = self.lat.to_radians();
+        let lat2 = other.lat.to_radians();
+        let delta_lon = (other.lon - self.lon).to_radians();
+
+        let y = delta_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+        (y.atan2(x).to_degrees() + 360.0) % 360.0
+    }
 }
 
 /// A directed and weighted graph edge
@@ -104,8 +124,12 @@ pub struct Graph {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     offsets: Vec<usize>,
+    weights: Vec<f64>,
     pub num_nodes: usize,
     pub num_edges: usize,
+    /// Centralities that have been computed already or are being computed
+    #[serde(skip)]
+    centralities: Mutex<HashMap<Centrality, CentralityCell>>,
 }
 
 /// Unstable float comparison.
@@ -157,10 +181,13 @@ impl Graph {
             let mut split = line.split(" ");
             line_no += 1;
             split.next(); // id
-            split.next(); // second id
 
             let node = Node {
                 id: i,
+                osm_id: split.next()
+                    .expect(&format!("Unexpected EOL while parsing node OSM id in line {}",
+                                     line_no))
+                    .parse()?,
                 lat: split.next()
                     .expect(&format!("Unexpected EOL while parsing node latitude in line {}",
                                      line_no))
@@ -218,11 +245,132 @@ impl Graph {
             nodes,
             edges,
             offsets,
+            weights: vec![1.0; num_nodes],
             num_nodes,
             num_edges,
+            centralities: Mutex::default(),
         })
     }
 
+    /// Load node weights from a file and assign them to the nodes of this graph.
+    /// Nodes without a weight in the file keep their current weight, which is `1` by default.
+    ///
+    /// The first non-comment line of the file specifies whether the nodes are keyed by their
+    /// graph id (`node`) or by their OpenStreetMap id (`osm`). Each following line consists of
+    /// a key and a non-negative weight, separated by a space.
+    pub fn load_weights_from_file(&mut self, weights_file_path: &str) -> Result<(), ParseError> {
+        let weights_file = File::open(weights_file_path)?;
+        let weights_reader = BufReader::new(weights_file);
+
+        log::debug!("Start parsing node weights: {}", weights_file_path);
+
+        let mut lines = weights_reader.lines()
+            .filter(|line| !matches!(line, Ok(line) if line.starts_with('#') || line.trim().is_empty()));
+
+        let key_type = match lines.next() {
+            Some(line) => line?.trim().to_string(),
+            None => return Err(ParseError::InvalidWeights("Missing key type".to_string()))
+        };
+        let node_ids: HashMap<u64, usize> = match key_type.as_str() {
+            "node" => (0..self.num_nodes).map(|id| (id as u64, id)).collect(),
+            "osm" => self.nodes.iter().map(|node| (node.osm_id, node.id)).collect(),
+            _ => return Err(ParseError::InvalidWeights(format!("Unknown key type: {}", key_type)))
+        };
+
+        let mut num_weights = 0;
+        for line in lines {
+            let line = line?;
+            let mut split = line.split_whitespace();
+            let key: u64 = split.next()
+                .ok_or_else(|| ParseError::InvalidWeights(format!("Missing key: {}", line)))?
+                .parse()?;
+            let weight: f64 = split.next()
+                .ok_or_else(|| ParseError::InvalidWeights(format!("Missing weight: {}", line)))?
+                .parse()?;
+
+            let node_id = *node_ids.get(&key)
+                .ok_or_else(|| ParseError::InvalidWeights(format!("Unknown {} id: {}", key_type, key)))?;
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(ParseError::InvalidWeights(
+                    format!("Weight must be finite and non-negative: {}", weight)));
+            }
+            self.weights[node_id] = weight;
+            num_weights += 1;
+        }
+        log::debug!("Parsed {} node weights", num_weights);
+
+        Ok(())
+    }
+
+    /// Get the weight of the node with id `node_id`
+    pub fn get_node_weight(&self, node_id: usize) -> f64 {
+        self.weights[node_id]
+    }
+
+    /// Get the sum of the weights of all nodes
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    /// Compute a 64-bit FNV-1a hash of the nodes, edges and node weights of this graph, which
+    /// identifies the graph independently of its name
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut write = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+
+        write(self.num_nodes as u64);
+        write(self.num_edges as u64);
+        for node in &self.nodes {
+            write(node.osm_id);
+            write(node.lat.to_bits());
+            write(node.lon.to_bits());
+        }
+        for edge in &self.edges {
+            write(edge.src as u64);
+            write(edge.tgt as u64);
+            write(edge.dist as u64);
+        }
+        for weight in &self.weights {
+            write(weight.to_bits());
+        }
+
+        hash
+    }
+
+    /// Get the centrality `centrality` of all nodes, which is computed on first use and cached in
+    /// this graph. The computation only blocks concurrent requests of the same centrality.
+    /// Only the sampled betweenness of the last requested sample count is cached, so that the
+    /// cache does not grow with the requested sample counts.
+    pub fn centrality(&self, centrality: Centrality) -> Arc<Vec<f64>> {
+        let centrality = centrality.for_num_nodes(self.num_nodes);
+        let cell = {
+            let mut centralities = self.centralities.lock().unwrap();
+            if let Centrality::SampledBetweenness { .. } = centrality {
+                centralities.retain(|&cached, _| cached == centrality
+                    || !matches!(cached, Centrality::SampledBetweenness { .. }));
+            }
+            centralities.entry(centrality).or_default().clone()
+        };
+        cell.get_or_init(|| {
+            log::debug!("Computing {:?} centrality of {} nodes", centrality, self.num_nodes);
+            Arc::new(centrality::compute(self, centrality))
+        }).clone()
+    }
+
+    /// Get the number of centralities that are cached in this graph
+    #[cfg(test)]
+    pub(crate) fn num_cached_centralities(&self) -> usize {
+        self.centralities.lock().unwrap().len()
+    }
+
     /// Returns a reference to the vector containing all graph nodes
     pub fn nodes(&self) -> &Vec<Node> {
         &self.nodes
@@ -250,14 +398,61 @@ impl Graph {
 
     /// Run an one-to-all Dijkstra from the source node with id `src_id`
     pub fn run_dijkstra(&self, src_ids: &[usize]) -> DijkstraResult {
+        self.run_dijkstra_with_preds(src_ids).0
+    }
+
+    /// Run an one-to-all Dijkstra from the source nodes with ids `src_ids` and additionally
+    /// return the predecessor of each node on its shortest path. The predecessor of sources
+    /// and unreachable nodes is `usize::MAX`.
+    pub fn run_dijkstra_with_preds(&self, src_ids: &[usize]) -> (DijkstraResult, Vec<usize>) {
+        let sources: Vec<_> = src_ids.iter()
+            .map(|&src_id| (src_id, 0))
+            .collect();
+        self.dijkstra(&sources, |_| true)
+    }
+
+    /// Run a many-to-all Dijkstra from the source nodes in `sources`, which are given as tuples
+    /// of the source node id and the initial distance of the source node
+    pub fn run_dijkstra_with_offsets(&self, sources: &[(usize, usize)]) -> DijkstraResult {
+        self.dijkstra(sources, |_| true).0
+    }
+
+    /// Run an one-to-all Dijkstra from the source nodes with ids `src_ids` that only relaxes
+    /// edges for which `is_passable` returns true and additionally return the predecessor of
+    /// each node on its shortest path
+    pub fn run_dijkstra_with_filter<F>(&self, src_ids: &[usize], is_passable: F) -> (DijkstraResult, Vec<usize>)
+        where F: Fn(&Edge) -> bool {
+        let sources: Vec<_> = src_ids.iter()
+            .map(|&src_id| (src_id, 0))
+            .collect();
+        self.dijkstra(&sources, is_passable)
+    }
+
+    /// Run a many-to-all Dijkstra from the source nodes in `sources`, which are given as tuples
+    /// of the source node id and the initial distance of the source node, that only relaxes
+    /// edges for which `is_passable` returns true and additionally return the predecessor of
+    /// each node on its shortest path
+    pub fn run_dijkstra_with_offsets_and_filter<F>(&self, sources: &[(usize, usize)], is_passable: F) -> (DijkstraResult, Vec<usize>)
+        where F: Fn(&Edge) -> bool {
+        self.dijkstra(sources, is_passable)
+    }
+
+    /// Run a many-to-all Dijkstra from the source nodes with initial distances in `sources` that
+    /// only relaxes edges for which `is_passable` returns true and return the distances and
+    /// predecessors of all nodes
+    fn dijkstra<F>(&self, sources: &[(usize, usize)], is_passable: F) -> (DijkstraResult, Vec<usize>)
+        where F: Fn(&Edge) -> bool {
         let mut distances = vec![usize::MAX; self.num_nodes];
-        for &src_id in src_ids {
-            distances[src_id] = 0;
+        let mut preds = vec![usize::MAX; self.num_nodes];
+        for &(src_id, offset) in sources {
+            distances[src_id] = distances[src_id].min(offset);
         }
 
         let mut pq = BinaryMinHeap::with_capacity(self.num_nodes);
-        for &src_id in src_ids {
-            pq.push(src_id, &distances);
+        for &(src_id, _) in sources {
+            if !pq.contains(src_id) {
+                pq.push(src_id, &distances);
+            }
         }
 
         while !pq.is_empty() {
@@ -265,10 +460,14 @@ impl Graph {
 
             for i in self.offsets[node]..self.offsets[node +1] {
                 let edge = &self.edges[i];
+                if !is_passable(edge) {
+                    continue;
+                }
                 let dist = distances[node] + edge.dist;
 
                 if dist < distances[edge.tgt] {
                     distances[edge.tgt] = dist;
+                    preds[edge.tgt] = node;
 
                     if pq.contains(edge.tgt) {
                         pq.decrease_key(edge.tgt, &distances);
@@ -279,7 +478,142 @@ impl Graph {
             }
         }
 
-        distances
+        (distances, preds)
+    }
+
+    /// Compute a minimum vertex cut that separates the source nodes with ids `src_ids` from the
+    /// sink nodes with ids `sink_ids` in the subgraph without the nodes for which `is_removed`
+    /// returns true. Sources are never part of the cut, but sinks may be. Sinks that are also
+    /// sources are ignored.
+    /// Among all minimum cuts, the one closest to the sinks is returned.
+    pub fn min_vertex_cut<F>(&self, src_ids: &[usize], sink_ids: &[usize], is_removed: F) -> Vec<usize>
+        where F: Fn(usize) -> bool {
+        // Split each node `v` into an in-node `2v` and an out-node `2v + 1`, connected by an arc
+        // with capacity 1, or infinite capacity for sources. Graph edges get infinite capacity.
+        let super_src = 2 * self.num_nodes;
+        let super_sink = super_src + 1;
+        let mut network = FlowNetwork::new(super_sink + 1);
+
+        let mut is_source = vec![false; self.num_nodes];
+        for &src_id in src_ids {
+            is_source[src_id] = !is_removed(src_id);
+        }
+        let removed: Vec<_> = (0..self.num_nodes)
+            .map(|node_id| !is_source[node_id] && is_removed(node_id))
+            .collect();
+
+        for node_id in (0..self.num_nodes).filter(|&node_id| !removed[node_id]) {
+            let cap = if is_source[node_id] { usize::MAX } else { 1 };
+            network.add_arc(2 * node_id, 2 * node_id + 1, cap);
+            if is_source[node_id] {
+                network.add_arc(super_src, 2 * node_id + 1, usize::MAX);
+            }
+        }
+        for edge in self.edges.iter().filter(|edge| !removed[edge.src] && !removed[edge.tgt]) {
+            network.add_arc(2 * edge.src + 1, 2 * edge.tgt, usize::MAX);
+        }
+        for &sink_id in sink_ids.iter().filter(|&&sink_id| !removed[sink_id] && !is_source[sink_id]) {
+            network.add_arc(2 * sink_id + 1, super_sink, usize::MAX);
+        }
+
+        network.max_flow(super_src, super_sink);
+
+        // The cut consists of all nodes whose out-node can reach the super sink in the residual
+        // network while their in-node cannot
+        let reaches_sink = network.residual_reaching(super_sink);
+        (0..self.num_nodes)
+            .filter(|&node_id| !removed[node_id] && !is_source[node_id])
+            .filter(|&node_id| reaches_sink[2 * node_id + 1] && !reaches_sink[2 * node_id])
+            .collect()
+    }
+
+    /// Compute the dominator tree of the subgraph without the nodes for which `is_removed`
+    /// returns true, rooted at a virtual super source that is connected to the source nodes with
+    /// ids `src_ids`. A node dominates another node if all paths from the sources to the other
+    /// node pass it.
+    /// Returns the immediate dominator of each node and the nodes reachable from the sources in
+    /// reverse postorder, in which every node precedes all nodes it dominates. The immediate
+    /// dominator of nodes that are only dominated by the super source and of unreachable nodes
+    /// is `usize::MAX`.
+    pub fn dominator_tree<F>(&self, src_ids: &[usize], is_removed: F) -> (Vec<usize>, Vec<usize>)
+        where F: Fn(usize) -> bool {
+        let super_src = self.num_nodes;
+        let removed: Vec<_> = (0..self.num_nodes).map(&is_removed).collect();
+        let mut is_source = vec![false; self.num_nodes];
+        for &src_id in src_ids {
+            is_source[src_id] = !removed[src_id];
+        }
+
+        // Number the reachable nodes in postorder by an iterative depth-first search
+        let mut postorder = vec![usize::MAX; self.num_nodes + 1];
+        let mut order = Vec::new();
+        let mut visited = vec![false; self.num_nodes];
+        let mut stack = Vec::new();
+        for src_id in (0..self.num_nodes).filter(|&node_id| is_source[node_id]) {
+            if visited[src_id] {
+                continue;
+            }
+            visited[src_id] = true;
+            stack.push((src_id, self.offsets[src_id]));
+            while let Some((node_id, next_edge)) = stack.pop() {
+                if next_edge == self.offsets[node_id + 1] {
+                    postorder[node_id] = order.len();
+                    order.push(node_id);
+                    continue;
+                }
+                stack.push((node_id, next_edge + 1));
+                let tgt = self.edges[next_edge].tgt;
+                if !visited[tgt] && !removed[tgt] {
+                    visited[tgt] = true;
+                    stack.push((tgt, self.offsets[tgt]));
+                }
+            }
+        }
+        postorder[super_src] = order.len();
+        order.reverse();
+
+        let mut in_edges = vec![Vec::new(); self.num_nodes];
+        for edge in self.edges.iter().filter(|edge| visited[edge.src] && visited[edge.tgt]) {
+            in_edges[edge.tgt].push(edge.src);
+        }
+
+        // Iterate the immediate dominators to a fixpoint as in the algorithm of Cooper, Harvey
+        // and Kennedy, where dominators are intersected by walking up the tree
+        let mut idoms = vec![usize::MAX; self.num_nodes + 1];
+        idoms[super_src] = super_src;
+        let intersect = |idoms: &[usize], mut node1: usize, mut node2: usize| {
+            while node1 != node2 {
+                while postorder[node1] < postorder[node2] {
+                    node1 = idoms[node1];
+                }
+                while postorder[node2] < postorder[node1] {
+                    node2 = idoms[node2];
+                }
+            }
+            node1
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node_id in &order {
+                let preds = is_source[node_id].then_some(super_src).into_iter()
+                    .chain(in_edges[node_id].iter().copied());
+                let mut idom = usize::MAX;
+                for pred in preds.filter(|&pred| idoms[pred] != usize::MAX) {
+                    idom = if idom == usize::MAX { pred } else { intersect(&idoms, pred, idom) };
+                }
+                if idoms[node_id] != idom {
+                    idoms[node_id] = idom;
+                    changed = true;
+                }
+            }
+        }
+
+        idoms.truncate(self.num_nodes);
+        for idom in idoms.iter_mut().filter(|idom| **idom == super_src) {
+            *idom = usize::MAX;
+        }
+        (idoms, order)
     }
 
     /// Returns this graphs grid bounds, i.e. the minimal/maximal latitude/longitude
@@ -311,12 +645,103 @@ impl Graph {
     }
 }
 
+/// Flow network with integral capacities that is used to compute minimum cuts.
+/// Arcs are stored in pairs, such that the reverse arc of arc `i` is arc `i ^ 1`.
+struct FlowNetwork {
+    arcs: Vec<Vec<usize>>,
+    heads: Vec<usize>,
+    caps: Vec<usize>,
+}
+
+impl FlowNetwork {
+    /// Create a new flow network with `num_vertices` vertices and no arcs
+    fn new(num_vertices: usize) -> Self {
+        Self {
+            arcs: vec![Vec::new(); num_vertices],
+            heads: Vec::new(),
+            caps: Vec::new(),
+        }
+    }
+
+    /// Add an arc from `src` to `tgt` with capacity `cap` and its reverse arc
+    fn add_arc(&mut self, src: usize, tgt: usize, cap: usize) {
+        self.arcs[src].push(self.heads.len());
+        self.heads.push(tgt);
+        self.caps.push(cap);
+        self.arcs[tgt].push(self.heads.len());
+        self.heads.push(src);
+        self.caps.push(0);
+    }
+
+    /// Push the maximum flow from `src` to `sink` along shortest augmenting paths.
+    /// Every augmenting path must contain an arc with capacity 1.
+    fn max_flow(&mut self, src: usize, sink: usize) -> usize {
+        let mut flow = 0;
+        loop {
+            // Find a shortest augmenting path with a BFS
+            let mut pred_arcs = vec![usize::MAX; self.arcs.len()];
+            let mut visited = vec![false; self.arcs.len()];
+            let mut queue = VecDeque::from([src]);
+            visited[src] = true;
+            while let Some(vertex) = queue.pop_front() {
+                if vertex == sink {
+                    break;
+                }
+                for &arc in &self.arcs[vertex] {
+                    let head = self.heads[arc];
+                    if self.caps[arc] > 0 && !visited[head] {
+                        visited[head] = true;
+                        pred_arcs[head] = arc;
+                        queue.push_back(head);
+                    }
+                }
+            }
+            if !visited[sink] {
+                return flow;
+            }
+
+            // Augment one unit of flow along the path
+            let mut vertex = sink;
+            while vertex != src {
+                let arc = pred_arcs[vertex];
+                if self.caps[arc] != usize::MAX {
+                    self.caps[arc] -= 1;
+                }
+                if self.caps[arc ^ 1] != usize::MAX {
+                    self.caps[arc ^ 1] += 1;
+                }
+                vertex = self.heads[arc ^ 1];
+            }
+            flow += 1;
+        }
+    }
+
+    /// Returns for each vertex whether it can reach `sink` in the residual network
+    fn residual_reaching(&self, sink: usize) -> Vec<bool> {
+        let mut reaching = vec![false; self.arcs.len()];
+        let mut queue = VecDeque::from([sink]);
+        reaching[sink] = true;
+        while let Some(vertex) = queue.pop_front() {
+            // The reverse of each outgoing arc is an incoming arc
+            for &arc in &self.arcs[vertex] {
+                let tail = self.heads[arc];
+                if self.caps[arc ^ 1] > 0 && !reaching[tail] {
+                    reaching[tail] = true;
+                    queue.push_back(tail);
+                }
+            }
+        }
+        reaching
+    }
+}
+
 #[derive(Debug)]
 pub enum ParseError {
     IO(std::io::Error),
     ParseInt(ParseIntError),
     ParseFloat(ParseFloatError),
     EmptyNodes,
+    InvalidWeights(String),
 }
 
 impl std::fmt::Display for ParseError {
@@ -326,6 +751,7 @@ impl std::fmt::Display for ParseError {
             Self::ParseInt(err) => write!(f, "{}", err.to_string()),
             Self::ParseFloat(err) => write!(f, "{}", err.to_string()),
             Self::EmptyNodes => write!(f, "Graph must consist of at least one node"),
+            Self::InvalidWeights(reason) => write!(f, "Invalid node weights: {}", reason),
         }
     }
 }
@@ -337,6 +763,7 @@ impl std::error::Error for ParseError {
             Self::ParseInt(ref err) => Some(err),
             Self::ParseFloat(ref err) => Some(err),
             Self::EmptyNodes => None,
+            Self::InvalidWeights(_) => None,
         }
     }
 }
@@ -364,7 +791,7 @@ mod test {
     use std::cmp::min;
     use rand::prelude::*;
 
-    use crate::graph::Graph;
+    use crate::graph::{Graph, Node};
 
     #[test]
     fn test_nodes_edges() {
@@ -416,6 +843,75 @@ mod test {
         assert_eq!(min(dists2[tgt], dists3[tgt]), dists1[tgt]);
     }
 
+    #[test]
+    fn test_offsets_dists() {
+        let graph =
+            Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+
+        let dists1 = graph.run_dijkstra(&[0]);
+        let dists2 = graph.run_dijkstra(&[1]);
+        let dists = graph.run_dijkstra_with_offsets(&[(0, 0), (1, 100)]);
+
+        for node in 0..graph.num_nodes {
+            let expected = min(dists1[node], dists2[node].saturating_add(100));
+            assert_eq!(dists[node], expected);
+        }
+    }
+
+    #[test]
+    fn test_preds() {
+        let graph =
+            Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+
+        let (dists, preds) = graph.run_dijkstra_with_preds(&[0]);
+        assert_eq!(preds[0], usize::MAX);
+        for node in 1..graph.num_nodes {
+            let pred = preds[node];
+            if dists[node] < usize::MAX {
+                let edge_dist = graph.get_outgoing_edges(pred).iter()
+                    .filter(|e| e.tgt == node)
+                    .map(|e| e.dist)
+                    .min()
+                    .unwrap();
+                assert_eq!(dists[pred] + edge_dist, dists[node]);
+            } else {
+                assert_eq!(pred, usize::MAX);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bearing() {
+        let origin = Node { id: 0, lat: 48.0, lon: 9.0, ..Node::default() };
+        let north = Node { id: 1, lat: 48.1, lon: 9.0, ..Node::default() };
+        let east = Node { id: 2, lat: 48.0, lon: 9.1, ..Node::default() };
+        let south_west = Node { id: 3, lat: 47.9, lon: 8.9, ..Node::default() };
+
+        assert!(origin.bearing_to(&north).abs() < 1e-6);
+        assert!((origin.bearing_to(&east) - 90.0).abs() < 0.1);
+        let bearing = origin.bearing_to(&south_west);
+        assert!(bearing > 180.0 && bearing < 270.0, "bearing: {}", bearing);
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut graph =
+            Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        assert_eq!(graph.total_weight(), graph.num_nodes as f64);
+
+        let path = std::env::temp_dir().join("osmff_test_weights.weights");
+        std::fs::write(&path, format!("# Test weights\nosm\n{} 5.5\n", graph.nodes[3].osm_id))
+            .unwrap();
+        graph.load_weights_from_file(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(graph.get_node_weight(3), 5.5);
+        assert_eq!(graph.get_node_weight(4), 1.0);
+        assert_eq!(graph.total_weight(), graph.num_nodes as f64 + 4.5);
+
+        std::fs::write(&path, "node\n100000 1.0\n").unwrap();
+        assert!(graph.load_weights_from_file(path.to_str().unwrap()).is_err());
+    }
+
     #[test]
     fn test_offsets() {
         let graph =
@@ -425,4 +921,106 @@ mod test {
         offsets_clone.sort();
         assert_eq!(offsets_clone, graph.offsets);
     }
+
+    #[test]
+    fn test_min_vertex_cut() {
+        let graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+
+        // Separating a node from its neighbors requires cutting all of its neighbors
+        let src_id = 0;
+        let neighbors: Vec<_> = graph.get_outgoing_edges(src_id).iter()
+            .map(|edge| edge.tgt)
+            .collect();
+        let far_id = graph.run_dijkstra(&[src_id]).iter()
+            .enumerate()
+            .filter(|&(_, &dist)| dist < usize::MAX)
+            .max_by_key(|&(_, &dist)| dist)
+            .unwrap().0;
+        let cut = graph.min_vertex_cut(&[src_id], &[far_id], |_| false);
+        assert!(!cut.is_empty() && cut.len() <= neighbors.len());
+        assert!(!cut.contains(&src_id));
+
+        // Removing the cut disconnects the sink from the source
+        let dists = graph.run_dijkstra(&[src_id]);
+        let reachable_without_cut = |tgt: usize| {
+            let mut visited = vec![false; graph.num_nodes];
+            let mut stack = vec![src_id];
+            visited[src_id] = true;
+            while let Some(node) = stack.pop() {
+                for edge in graph.get_outgoing_edges(node) {
+                    if !visited[edge.tgt] && !cut.contains(&edge.tgt) {
+                        visited[edge.tgt] = true;
+                        stack.push(edge.tgt);
+                    }
+                }
+            }
+            visited[tgt]
+        };
+        assert!(dists[far_id] < usize::MAX);
+        assert!(!reachable_without_cut(far_id));
+
+        // Removing all neighbors of the source leaves nothing to cut
+        let cut = graph.min_vertex_cut(&[src_id], &[far_id], |node_id| neighbors.contains(&node_id));
+        assert!(cut.is_empty());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        let same_graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        let other_graph = Graph::parse_from_file("data/stgcenter_undirected.fmi").unwrap();
+
+        assert_eq!(graph.content_hash(), same_graph.content_hash());
+        assert_ne!(graph.content_hash(), other_graph.content_hash());
+    }
+
+    #[test]
+    fn test_dominator_tree() {
+        let graph = Graph::parse_from_file("data/bbgrund_undirected.fmi").unwrap();
+        let src_ids = [0, 100];
+        let removed = [5, 50];
+        let (idoms, order) = graph.dominator_tree(&src_ids, |node_id| removed.contains(&node_id));
+
+        let reachable_without = |node: usize| {
+            let mut visited = vec![false; graph.num_nodes];
+            let mut stack: Vec<_> = src_ids.iter().copied().filter(|&src_id| src_id != node).collect();
+            for &src_id in &stack {
+                visited[src_id] = true;
+            }
+            while let Some(node_id) = stack.pop() {
+                for edge in graph.get_outgoing_edges(node_id) {
+                    if !visited[edge.tgt] && edge.tgt != node && !removed.contains(&edge.tgt) {
+                        visited[edge.tgt] = true;
+                        stack.push(edge.tgt);
+                    }
+                }
+            }
+            visited
+        };
+
+        // Exactly the reachable nodes are ordered, dominators first
+        let reachable = reachable_without(usize::MAX);
+        assert_eq!(order.len(), reachable.iter().filter(|&&is_reachable| is_reachable).count());
+        let mut position = vec![usize::MAX; graph.num_nodes];
+        for (i, &node_id) in order.iter().enumerate() {
+            position[node_id] = i;
+        }
+        for &node_id in &order {
+            assert!(idoms[node_id] == usize::MAX || position[idoms[node_id]] < position[node_id]);
+        }
+
+        // A node dominates exactly the nodes that become unreachable without it
+        for dominator in (0..graph.num_nodes).step_by(7).filter(|&node_id| reachable[node_id]) {
+            let reachable_without_dominator = reachable_without(dominator);
+            for &node_id in order.iter().filter(|&&node_id| node_id != dominator) {
+                let mut is_dominated = false;
+                let mut ancestor = idoms[node_id];
+                while ancestor != usize::MAX {
+                    is_dominated |= ancestor == dominator;
+                    ancestor = idoms[ancestor];
+                }
+                assert_eq!(is_dominated, !reachable_without_dominator[node_id]);
+            }
+        }
+    }
 }
\ No newline at end of file
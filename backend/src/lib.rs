@@ -1,3 +1,4 @@
+pub mod centrality;
 pub mod graph;
 pub mod firefighter;
 pub(crate) mod binary_minheap;
@@ -5,6 +6,7 @@ pub(crate) mod binary_minheap;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 
 use crate::graph::Graph;
@@ -34,7 +36,17 @@ pub fn load_graphs(graphs_path: &str) -> Result<HashMap<String, Arc<Graph>>, Box
             let mut graphs = HashMap::with_capacity(graph_data.len());
             for (graph_name, graph_path) in graph_data {
                 match Graph::parse_from_file(&graph_path) {
-                    Ok(graph) => {
+                    Ok(mut graph) => {
+                        // Load node weights from an optional sidecar file
+                        let weights_path = Path::new(&graph_path).with_extension("weights");
+                        if weights_path.exists() {
+                            if let Err(err) = graph.load_weights_from_file(
+                                weights_path.to_str().expect("Invalid unicode path")) {
+                                log::warn!("Failed to parse node weights of graph: {}", &graph_name);
+                                return Err(err.into());
+                            }
+                            log::info!("Parsed node weights of graph: {}", &graph_name);
+                        }
                         log::info!("Parsed graph: {}", &graph_name);
                         graphs.insert(graph_name, Arc::new(graph))
                     }
@@ -8,7 +8,9 @@ use log;
 use serde::{Serialize, Deserialize};
 use serde_json::json;
 
-use osmff_lib::firefighter::problem::{OSMFProblem, OSMFSettings};
+use osmff_lib::firefighter::monte_carlo;
+use osmff_lib::firefighter::problem::{OSMFDefensePlan, OSMFProblem, OSMFSettings};
+use osmff_lib::firefighter::record::OSMFSimulationRecord;
 use osmff_lib::firefighter::strategy::OSMFStrategy;
 use osmff_lib::firefighter::TimeUnit;
 use osmff_lib::graph::Graph;
@@ -27,6 +29,12 @@ struct Config {
     port: u16,
     log_level: String,
     graphs_path: String,
+    /// Maximum number of runs of a single Monte Carlo simulation request
+    #[serde(default = "Config::default_max_monte_carlo_runs")]
+    max_monte_carlo_runs: usize,
+    /// Maximum size in bytes of an imported simulation record
+    #[serde(default = "Config::default_max_import_size")]
+    max_import_size: usize,
 }
 
 impl Config {
@@ -36,12 +44,21 @@ impl Config {
             .expect("Could not find config file");
         serde_json::from_str(&data).expect("Config file does not contain valid JSON")
     }
+
+    fn default_max_monte_carlo_runs() -> usize {
+        1000
+    }
+
+    fn default_max_import_size() -> usize {
+        64 * 1024 * 1024
+    }
 }
 
 /// Storage for data associated to the web app
 struct AppData {
     sessions: Mutex<OSMFSessionStorage>,
     graphs: HashMap<String, Arc<Graph>>,
+    max_monte_carlo_runs: usize,
 }
 
 #[derive(Serialize)]
@@ -50,6 +67,14 @@ struct GraphData {
     num_of_nodes: usize
 }
 
+/// Request to validate an external defense plan for a firefighter problem instance
+#[derive(Deserialize)]
+struct PlanValidationRequest {
+    settings: OSMFSettings,
+    #[serde(flatten)]
+    plan: OSMFDefensePlan,
+}
+
 /// Common function to initialize a `HttpResponseBuilder` for an incoming `HttpRequest`.
 /// This function must be called before retrieving session data.
 fn init_response(data: &web::Data<AppData>, req: &HttpRequest, mut res: HttpResponseBuilder) -> (HttpResponseBuilder, String) {
@@ -93,11 +118,12 @@ async fn list_graphs(data: web::Data<AppData>, req: HttpRequest) -> impl Respond
     ))
 }
 
-/// List all available firefighter containment strategies
+/// List all available firefighter containment strategies together with their tunable parameters.
+/// This service does not register custom strategies, so only the built-in strategies are listed.
 #[get("/strategies")]
 async fn list_strategies(data: web::Data<AppData>, req: HttpRequest) -> impl Responder {
     let (mut res, _) = init_response(&data, &req, HttpResponse::Ok());
-    res.json(json!(OSMFStrategy::available_strategies()))
+    res.json(json!(OSMFStrategy::available_strategies_with_parameters()))
 }
 
 /// Simulate a new firefighter problem instance
@@ -144,6 +170,67 @@ async fn simulate_problem(data: web::Data<AppData>, settings: web::Json<OSMFSett
     Ok(res)
 }
 
+/// Simulate a firefighter problem instance multiple times with consecutive seeds and
+/// aggregate the results
+#[post("/montecarlo")]
+async fn simulate_monte_carlo(data: web::Data<AppData>, settings: web::Json<OSMFSettings>, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
+    let (mut res, _) = init_response(&data, &req, HttpResponse::Ok());
+
+    let graph = match data.graphs.get(&settings.graph_name) {
+        Some(graph) => graph,
+        None => {
+            log::warn!("Unknown graph {}", settings.graph_name);
+            return Err(OSMFError::BadRequest {
+                message: format!("Unknown value for parameter 'graph': '{}'", settings.graph_name)
+            });
+        }
+    };
+
+    let query = Query::from(req.query_string());
+    let num_runs = query.get_and_parse::<usize>("runs")?;
+    if num_runs > data.max_monte_carlo_runs {
+        log::warn!("Too many Monte Carlo runs {}", num_runs);
+        return Err(OSMFError::BadRequest {
+            message: format!("Value for parameter 'runs' must be at most {}: {}",
+                             data.max_monte_carlo_runs, num_runs)
+        });
+    }
+
+    let results = monte_carlo::simulate_monte_carlo(graph.clone(), &settings, num_runs)?;
+
+    Ok(res.json(results))
+}
+
+/// Validate an external defense plan and simulate it if it does not violate any rules
+#[post("/validate")]
+async fn validate_plan(data: web::Data<AppData>, request: web::Json<PlanValidationRequest>, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
+    let (mut res, sid) = init_response(&data, &req, HttpResponse::Ok());
+    let PlanValidationRequest { settings, plan } = request.into_inner();
+
+    let graph = match data.graphs.get(&settings.graph_name) {
+        Some(graph) => graph,
+        None => {
+            log::warn!("Unknown graph {}", settings.graph_name);
+            return Err(OSMFError::BadRequest {
+                message: format!("Unknown value for parameter 'graph': '{}'", settings.graph_name)
+            });
+        }
+    };
+
+    let mut problem = OSMFProblem::with_plan(graph.clone(), settings, plan)?;
+    problem.simulate();
+
+    let res = res.json(problem.plan_validation_response());
+
+    {
+        let mut sessions = data.sessions.lock().unwrap();
+        let session = sessions.get_mut_session(&sid).unwrap();
+        session.attach_problem(problem);
+    }
+
+    Ok(res)
+}
+
 /// Display the view of a firefighter simulation
 #[get("/view")]
 async fn display_view(data: web::Data<AppData>, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
@@ -203,6 +290,92 @@ async fn get_sim_step_metadata(data: web::Data<AppData>, req: HttpRequest) -> Re
     Ok(res.json(problem.sim_step_metadata_response(&time)))
 }
 
+/// Get the number of burned and defended nodes at each step of a firefighter simulation
+#[get("/timeseries")]
+async fn get_time_series(data: web::Data<AppData>, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
+    let (mut res, sid) = init_response(&data, &req, HttpResponse::Ok());
+
+    let mut sessions = data.sessions.lock().unwrap();
+    let session = sessions.get_session(&sid).unwrap();
+    let problem = match session.get_problem() {
+        Some(problem) => problem,
+        None => {
+            return Err(OSMFError::NoSimulation {
+                message: "No simulation has been started yet".to_string()
+            });
+        }
+    };
+
+    Ok(res.json(problem.time_series_response()))
+}
+
+/// Export the complete results of a firefighter simulation as JSON or in a compact binary form
+#[get("/export")]
+async fn export_simulation(data: web::Data<AppData>, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
+    let (mut res, sid) = init_response(&data, &req, HttpResponse::Ok());
+
+    let mut sessions = data.sessions.lock().unwrap();
+    let session = sessions.get_session(&sid).unwrap();
+    let problem = match session.get_problem() {
+        Some(problem) => problem,
+        None => {
+            return Err(OSMFError::NoSimulation {
+                message: "No simulation has been started yet".to_string()
+            });
+        }
+    };
+
+    let query = Query::from(req.query_string());
+    let record = problem.to_record();
+    match query.try_get("format").unwrap_or("json") {
+        "json" => Ok(res.content_type("application/json")
+            .body(record.to_json())),
+        "binary" => Ok(res.content_type("application/octet-stream")
+            .body(record.to_binary())),
+        format => Err(OSMFError::BadRequest {
+            message: format!("Unknown value for parameter 'format': '{}'", format)
+        }),
+    }
+}
+
+/// Import the exported results of a firefighter simulation without re-running it
+#[post("/import")]
+async fn import_simulation(data: web::Data<AppData>, body: web::Bytes, req: HttpRequest) -> Result<HttpResponse, OSMFError> {
+    let (mut res, sid) = init_response(&data, &req, HttpResponse::Created());
+
+    let query = Query::from(req.query_string());
+    let record = match query.try_get("format").unwrap_or("json") {
+        "json" => OSMFSimulationRecord::from_json(&body)?,
+        "binary" => OSMFSimulationRecord::from_binary(&body)?,
+        format => {
+            return Err(OSMFError::BadRequest {
+                message: format!("Unknown value for parameter 'format': '{}'", format)
+            });
+        }
+    };
+
+    let graph = match data.graphs.get(&record.settings.graph_name) {
+        Some(graph) => graph,
+        None => {
+            log::warn!("Unknown graph {}", record.settings.graph_name);
+            return Err(OSMFError::BadRequest {
+                message: format!("Unknown graph in simulation record: '{}'", record.settings.graph_name)
+            });
+        }
+    };
+
+    let problem = OSMFProblem::from_record(graph.clone(), record)?;
+    let res = res.json(problem.simulation_response());
+
+    {
+        let mut sessions = data.sessions.lock().unwrap();
+        let session = sessions.get_mut_session(&sid).unwrap();
+        session.attach_problem(problem);
+    }
+
+    Ok(res)
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Parse config file
@@ -225,9 +398,11 @@ async fn main() -> std::io::Result<()> {
     let data = web::Data::new(AppData {
         sessions: Mutex::new(OSMFSessionStorage::new()),
         graphs,
+        max_monte_carlo_runs: config.max_monte_carlo_runs,
     });
 
     // Initialize and start server
+    let max_import_size = config.max_import_size;
     let server = HttpServer::new(move || {
         // Initialize cors settings
         let cors = Cors::default()
@@ -245,8 +420,16 @@ async fn main() -> std::io::Result<()> {
             .service(list_graphs)
             .service(list_strategies)
             .service(simulate_problem)
+            .service(simulate_monte_carlo)
+            .service(validate_plan)
             .service(display_view)
             .service(get_sim_step_metadata)
+            .service(get_time_series)
+            .service(export_simulation)
+            .service(web::scope("")
+                // Records of large graphs exceed the default payload limit
+                .app_data(web::PayloadConfig::new(max_import_size))
+                .service(import_simulation))
     });
     server.bind((config.host.as_str(), config.port))?
         .run()
@@ -2,6 +2,7 @@ use actix_web::{HttpResponse, http::StatusCode, ResponseError};
 use derive_more::{Display, Error};
 use serde::Serialize;
 use osmff_lib::firefighter::problem::OSMFSettingsError;
+use osmff_lib::firefighter::record::OSMFRecordError;
 
 /// Blueprint for error responses
 #[derive(Serialize)]
@@ -55,6 +56,17 @@ impl From<OSMFSettingsError> for OSMFError {
     }
 }
 
+impl From<OSMFRecordError> for OSMFError {
+    fn from(err: OSMFRecordError) -> Self {
+        match err {
+            OSMFRecordError::InvalidSettings { source } => source.into(),
+            _ => Self::BadRequest {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
 impl ResponseError for OSMFError {
     fn status_code(&self) -> StatusCode {
         match *self {
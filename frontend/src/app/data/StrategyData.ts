@@ -0,0 +1,11 @@
+export class StrategyParameter {
+  name: string;
+  description: string;
+  type: string;
+  default: number | string;
+}
+
+export class StrategyData {
+  name: string;
+  parameters: StrategyParameter[];
+}
\ No newline at end of file
@@ -1,12 +1,14 @@
 import { Injectable } from '@angular/core';
 import { HttpClient, HttpParams } from '@angular/common/http';
 import {Observable} from "rxjs";
+import {map} from "rxjs/operators";
 import { SimulationConfig } from '../data/SimulationConfig';
 import { Coordinates } from '../view-inputs/view-input/view-input.component';
 import { environment } from '../../environments/environment';
 import { SimulationMetaData } from '../data/SimulationMetaData';
 import { StepMetaData } from '../data/StepMetaData';
 import { GraphData } from '../data/GraphData';
+import { StrategyData } from '../data/StrategyData';
 
 @Injectable({
   providedIn: 'root'
@@ -26,7 +28,8 @@ export class GraphServiceService {
   }
 
   getStrategies(): Observable<string[]> {
-    return this.http.get<string[]>(this.path + "/strategies")
+    return this.http.get<StrategyData[]>(this.path + "/strategies")
+      .pipe(map(strategies => strategies.map(strategy => strategy.name)))
   }
 
   simulate(config: SimulationConfig): Observable<SimulationMetaData> {